@@ -1,5 +1,5 @@
 use criterion::{criterion_group, criterion_main, Criterion};
-use onagre::db::desktop_entry::DesktopEntryEntity;
+use onagre::db::desktop_entry::{DesktopEntryEntity, COLLECTION};
 use onagre::db::Database;
 
 fn open_db(c: &mut Criterion) {
@@ -14,7 +14,7 @@ fn get_all_desktop_entries(c: &mut Criterion) {
     let db = Database::default();
     c.bench_function("get all history entry", |b| {
         b.iter(|| {
-            db.get_all::<DesktopEntryEntity>();
+            db.get_all::<DesktopEntryEntity>(COLLECTION);
         })
     });
 }
@@ -1,11 +1,18 @@
 use criterion::{criterion_group, criterion_main, Criterion};
-use onagre::freedesktop::IconFinder;
+use onagre::icons::IconPath;
 
 fn icon_lookup(c: &mut Criterion) {
-    let finder = IconFinder::build("Arc").unwrap();
+    let themes = vec!["Adwaita".to_string()];
+
     c.bench_function("lookup firefox icon", |b| {
         b.iter(|| {
-            finder.lookup("firefox", 24);
+            IconPath::find("firefox", &themes, 24, 1);
+        })
+    });
+
+    c.bench_function("lookup cached firefox icon", |b| {
+        b.iter(|| {
+            IconPath::lookup("firefox", &themes, 24);
         })
     });
 }
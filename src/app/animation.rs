@@ -0,0 +1,257 @@
+use crate::app::mode::WINDOW_MANAGER;
+use crate::windows::WindowManager;
+use crate::THEME;
+use iced::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Interval of the animation clock subscription
+pub const FRAME: Duration = Duration::from_millis(16);
+
+// Each opacity change spawns `xprop` on X11, the window fades in a few steps there
+const X11_FRAME: Duration = Duration::from_millis(50);
+
+static EXITING: AtomicBool = AtomicBool::new(false);
+
+// CSS timing functions accepted by `--transition`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    Ease,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    // Cubic approximations of the CSS curves
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::Ease | Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (2.0 - 2.0 * t).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transition {
+    pub duration: Duration,
+    pub easing: Easing,
+}
+
+impl Transition {
+    // Eased progress from 0 to 1 of a transition started at `started`
+    pub fn progress(&self, started: Instant, now: Instant) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+
+        let elapsed = now.saturating_duration_since(started);
+        self.easing
+            .apply(elapsed.as_secs_f32() / self.duration.as_secs_f32())
+    }
+}
+
+// Running transitions, advanced by the animation clock
+#[derive(Debug, Default)]
+pub struct Animations {
+    // Last tick of the clock
+    now: Option<Instant>,
+    selected: Option<usize>,
+    // Row losing the selection and when the selection changed
+    selection: Option<(Option<usize>, Instant)>,
+    // Viewport offset before it moved and when it moved
+    scroll: Option<(usize, Instant)>,
+}
+
+impl Animations {
+    pub fn is_running(&self) -> bool {
+        self.selection.is_some() || self.scroll.is_some()
+    }
+
+    pub fn select(&mut self, selected: Option<usize>) {
+        if selected == self.selected {
+            return;
+        }
+
+        if THEME.transition().is_some() {
+            self.selection = Some((self.selected, Instant::now()));
+        }
+
+        self.selected = selected;
+    }
+
+    pub fn scroll(&mut self, from: usize, to: usize) {
+        if from != to && THEME.transition().is_some() {
+            self.scroll = Some((from, Instant::now()));
+        }
+    }
+
+    // Drop finished transitions, returns true when scrolling just ended
+    pub fn tick(&mut self, now: Instant) -> bool {
+        self.now = Some(now);
+        let transition = match THEME.transition() {
+            Some(transition) => transition,
+            None => {
+                let scrolling = self.scroll.is_some();
+                self.selection = None;
+                self.scroll = None;
+                return scrolling;
+            }
+        };
+
+        let done = |started: Instant| transition.progress(started, now) >= 1.0;
+        if matches!(self.selection, Some((_, started)) if done(started)) {
+            self.selection = None;
+        }
+
+        if matches!(self.scroll, Some((_, started)) if done(started)) {
+            self.scroll = None;
+            return true;
+        }
+
+        false
+    }
+
+    // The previously selected row and the selection progress
+    pub fn selection(&self) -> Option<(Option<usize>, f32)> {
+        self.selection
+            .map(|(previous, started)| (previous, self.progress(started)))
+    }
+
+    // The offset the viewport is moving from and the scroll progress
+    pub fn scroll_position(&self) -> Option<(usize, f32)> {
+        self.scroll
+            .map(|(from, started)| (from, self.progress(started)))
+    }
+
+    fn progress(&self, started: Instant) -> f32 {
+        match (THEME.transition(), self.now) {
+            (Some(transition), Some(now)) => transition.progress(started, now),
+            (Some(_), None) => 0.0,
+            (None, _) => 1.0,
+        }
+    }
+}
+
+// Iced has no window opacity, the window manager fades the window in.
+// Fades need sway/i3 IPC or an EWMH window manager, the window appears at once elsewhere.
+// Called when the window is first focused, it is mapped by then.
+pub fn fade_in(title: String) {
+    let (transition, window_manager) = match (THEME.transition(), WINDOW_MANAGER.as_ref()) {
+        (Some(transition), Some(window_manager)) => (*transition, window_manager),
+        _ => return,
+    };
+
+    thread::spawn(move || {
+        if window_manager.set_opacity(&title, 0.0).is_ok() {
+            fade(window_manager, &title, transition, |progress| progress);
+        }
+    });
+}
+
+// True once `exit` was called, input is ignored while the window fades out
+pub fn is_exiting() -> bool {
+    EXITING.load(Ordering::Relaxed)
+}
+
+// Fade the window out on a blocking task then exit, the UI keeps rendering meanwhile.
+// Exits right away when no window manager can fade the window.
+pub fn exit<M: Send + 'static>(code: i32) -> Command<M> {
+    let (transition, window_manager) = match (THEME.transition(), WINDOW_MANAGER.as_ref()) {
+        (Some(transition), Some(window_manager)) => (*transition, window_manager),
+        _ => std::process::exit(code),
+    };
+
+    if EXITING.swap(true, Ordering::Relaxed) {
+        return Command::none();
+    }
+
+    let fade_out = tokio::task::spawn_blocking(move || {
+        fade(window_manager, super::TITLE, transition, |progress| {
+            1.0 - progress
+        })
+    });
+
+    Command::perform(fade_out, move |_| std::process::exit(code))
+}
+
+fn fade<F>(window_manager: &WindowManager, title: &str, transition: Transition, opacity: F)
+where
+    F: Fn(f32) -> f32,
+{
+    let frame = match window_manager {
+        WindowManager::Ipc(_) => FRAME,
+        WindowManager::Ewmh => X11_FRAME,
+    };
+
+    let started = Instant::now();
+    loop {
+        let progress = transition.progress(started, Instant::now());
+        if window_manager
+            .set_opacity(title, opacity(progress))
+            .is_err()
+            || progress >= 1.0
+        {
+            return;
+        }
+
+        thread::sleep(frame);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::animation::{Easing, Transition};
+    use speculoos::prelude::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn should_ease_between_start_and_end() {
+        for easing in [
+            Easing::Linear,
+            Easing::Ease,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+        ]
+        .iter()
+        {
+            assert_that!(easing.apply(0.0)).is_equal_to(0.0);
+            assert_that!(easing.apply(1.0)).is_equal_to(1.0);
+            assert_that!(easing.apply(2.0)).is_equal_to(1.0);
+        }
+
+        assert_that!(Easing::EaseOut.apply(0.25)).is_greater_than(0.25);
+        assert_that!(Easing::EaseIn.apply(0.25)).is_less_than(0.25);
+        assert_that!(Easing::EaseInOut.apply(0.5)).is_equal_to(0.5);
+    }
+
+    #[test]
+    fn should_progress_with_elapsed_time() {
+        let transition = Transition {
+            duration: Duration::from_millis(100),
+            easing: Easing::Linear,
+        };
+        let started = Instant::now();
+
+        let progress =
+            |elapsed| transition.progress(started, started + Duration::from_millis(elapsed));
+
+        assert_that!(progress(0)).is_equal_to(0.0);
+        assert_that!(progress(50)).is_equal_to(0.5);
+        assert_that!(progress(150)).is_equal_to(1.0);
+        assert_that!(transition.progress(started + Duration::from_millis(10), started))
+            .is_equal_to(0.0);
+    }
+}
@@ -38,7 +38,7 @@ impl Cache<'_> {
     }
 
     pub fn de_len(&self) -> usize {
-        self.de_history.get().map(|de| de.len()).unwrap_or(0)
+        self.de_history().len()
     }
 
     pub fn plugin_history(&self, plug_name: &str) -> Rc<Vec<PluginCommandEntity>> {
@@ -52,12 +52,7 @@ impl Cache<'_> {
     }
 
     pub fn plugin_history_len(&self, plug_name: &str) -> usize {
-        self.plugin_history
-            .lock()
-            .unwrap()
-            .get(plug_name)
-            .map(|p| p.len())
-            .unwrap_or(0)
+        self.plugin_history(plug_name).len()
     }
 
     pub fn web_history(&self, web_name: &str) -> Rc<Vec<WebEntity>> {
@@ -71,11 +66,6 @@ impl Cache<'_> {
     }
 
     pub fn web_history_len(&self, web_name: &str) -> usize {
-        self.web_history
-            .lock()
-            .unwrap()
-            .get(web_name)
-            .map(|p| p.len())
-            .unwrap_or(0)
+        self.web_history(web_name).len()
     }
 }
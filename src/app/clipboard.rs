@@ -0,0 +1,56 @@
+use anyhow::anyhow;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// The part of the selected row copied to the clipboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyField {
+    Name,
+    Description,
+    Query,
+}
+
+// Write `text` to the clipboard using `wl-copy` on wayland and `xclip` on X11.
+// Both keep serving the selection in the background once onagre has exited,
+// which the clipboard owned by the iced window cannot do.
+pub fn copy(text: &str) -> anyhow::Result<()> {
+    let mut command = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        Command::new("wl-copy")
+    } else {
+        let mut command = Command::new("xclip");
+        command.args(["-selection", "clipboard"]);
+        command
+    };
+
+    let mut child = command.stdin(Stdio::piped()).spawn()?;
+
+    child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow!("Unable to open clipboard command stdin"))?
+        .write_all(text.as_bytes())?;
+
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!("Clipboard command exited with {status}"))
+    }
+}
+
+// Type `text` in the focused window using `wtype` on wayland and `xdotool` on X11.
+// Typing starts after a short delay so the previous window gets focus back once onagre exits.
+pub fn type_text(text: &str) -> anyhow::Result<()> {
+    let mut command = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        let mut command = Command::new("wtype");
+        command.args(["-s", "150", text]);
+        command
+    } else {
+        let mut command = Command::new("xdotool");
+        command.args(["sleep", "0.15", "type", text]);
+        command
+    };
+
+    command.spawn()?;
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use std::borrow::Cow;
+
+use crate::app::entries::AsEntry;
+use crate::bookmarks::Bookmark;
+use crate::icons::{pipeline, IconPath};
+use crate::THEME;
+
+impl<'a> AsEntry<'a> for Bookmark {
+    fn get_display_name(&self) -> &str {
+        self.title.as_str()
+    }
+
+    fn get_icon(&self) -> Option<IconPath> {
+        self.favicon
+            .as_deref()
+            .and_then(|favicon| pipeline::converted(favicon, THEME.icon_size))
+            .or_else(|| IconPath::fallback("bookmark"))
+    }
+
+    fn get_description(&self) -> Option<Cow<'_, str>> {
+        match &self.folder {
+            Some(folder) => Some(Cow::Owned(format!("{folder} · {}", self.url))),
+            None => Some(Cow::Borrowed(self.url.as_str())),
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use std::borrow::Cow;
+
+use crate::app::entries::AsEntry;
+use crate::calc::{self, CalcError};
+use crate::icons::IconPath;
+use crate::THEME;
+
+// The single row displayed by the built-in calculator
+#[derive(Debug)]
+pub struct CalcEntry {
+    pub expression: String,
+    pub result: Result<String, CalcError>,
+}
+
+impl CalcEntry {
+    pub fn new(expression: &str) -> Self {
+        CalcEntry {
+            expression: expression.to_string(),
+            result: calc::evaluate(expression),
+        }
+    }
+}
+
+impl<'a> AsEntry<'a> for CalcEntry {
+    fn get_display_name(&self) -> &str {
+        match &self.result {
+            Ok(result) => result.as_str(),
+            Err(_) => self.expression.as_str(),
+        }
+    }
+
+    fn get_icon(&self) -> Option<IconPath> {
+        match &THEME.icon_theme {
+            Some(theme) => IconPath::lookup("accessories-calculator", theme, THEME.icon_size)
+                .or_else(|| IconPath::fallback("calc")),
+            _ => None,
+        }
+    }
+
+    // Show the evaluated expression, or why it could not be evaluated
+    fn get_description(&self) -> Option<Cow<'_, str>> {
+        match &self.result {
+            Ok(_) => Some(Cow::Borrowed(self.expression.as_str())),
+            Err(err) => Some(Cow::Owned(err.to_string())),
+        }
+    }
+}
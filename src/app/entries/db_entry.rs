@@ -9,7 +9,7 @@ use crate::app::Message;
 use crate::db::desktop_entry::DesktopEntryEntity;
 use crate::db::plugin::PluginCommandEntity;
 use crate::db::web::WebEntity;
-use crate::icons::{Extension, IconPath};
+use crate::icons::{pipeline, Extension, IconPath};
 use crate::THEME;
 
 impl<'a> AsEntry<'a> for DesktopEntryEntity<'_> {
@@ -22,7 +22,8 @@ impl<'a> AsEntry<'a> for DesktopEntryEntity<'_> {
             Some(theme) => self
                 .icon
                 .as_deref()
-                .and_then(|name| IconPath::lookup(name, theme, THEME.icon_size)),
+                .and_then(|name| IconPath::lookup(name, theme, THEME.icon_size))
+                .or_else(|| IconPath::fallback("desktop")),
             _ => None,
         }
     }
@@ -42,8 +43,10 @@ impl<'a> AsEntry<'a> for PluginCommandEntity<'a> {
     where
         'b: 'a,
     {
-        let icon = Self::build_icon(&style.icon, category_icon);
-        Row::new().push(icon)
+        let icon = category_icon
+            .cloned()
+            .or_else(|| IconPath::fallback("plugin"));
+        Row::new().push(Self::build_icon(&style.icon, icon))
     }
 
     fn get_display_name(&self) -> &str {
@@ -71,26 +74,10 @@ impl<'a> AsEntry<'a> for WebEntity<'a> {
                 )
             })
             .and_then(|(path, filename)| {
-                // Unfortunately we need to copy .ico files to png extension for iced
-                // To render the icon
-                let path = path.join(format!("{}.png", &filename));
-                if path.exists() {
-                    Some(IconPath {
-                        path,
-                        extension: Extension::Png,
-                        symbolic: filename.ends_with("-symbolic"),
-                    })
-                } else if path.with_extension("ico").exists() {
-                    ico_to_png(path.with_extension("ico"));
-                    Some(IconPath {
-                        path,
-                        extension: Extension::Png,
-                        symbolic: filename.ends_with("-symbolic"),
-                    })
-                } else {
-                    None
-                }
+                let symbolic = filename.ends_with("-symbolic");
+                favicon(path.join(format!("{}.png", &filename)), symbolic)
             })
+            .or_else(|| IconPath::fallback("web"))
     }
 
     fn get_description(&self) -> Option<Cow<'_, str>> {
@@ -98,22 +85,20 @@ impl<'a> AsEntry<'a> for WebEntity<'a> {
     }
 }
 
-// FIXME: This should be removed
-fn ico_to_png(path: PathBuf) {
-    let file = std::fs::File::open(&path).unwrap();
-    match ico::IconDir::read(file) {
-        Ok(icon) => {
-            for entry in icon.entries() {
-                if !entry.is_png() {
-                    let image = entry.decode().unwrap();
-                    let file = std::fs::File::create(&path.with_extension("png")).unwrap();
-                    image.write_png(file).unwrap();
-                }
-            }
-        }
-        Err(_) => {
-            // We were unable to read the icon, it's probably a png
-            std::fs::copy(&path, path.with_extension("png")).unwrap();
-        }
+// pop-launcher caches web favicons as ico files, which iced can't render
+fn favicon(png: PathBuf, symbolic: bool) -> Option<IconPath> {
+    if png.exists() {
+        return Some(IconPath {
+            path: png,
+            extension: Extension::Png,
+            symbolic,
+        });
+    }
+
+    let ico = png.with_extension("ico");
+    if ico.exists() {
+        pipeline::converted(&ico, THEME.icon_size)
+    } else {
+        None
     }
 }
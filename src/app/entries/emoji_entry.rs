@@ -0,0 +1,70 @@
+use std::borrow::Cow;
+
+use crate::app::entries::AsEntry;
+use crate::emoji::{Emoji, SkinTone};
+use crate::icons::IconPath;
+use crate::{font, THEME};
+use iced::Font;
+use once_cell::sync::Lazy;
+
+static EMOJI_FONT: Lazy<Font> = Lazy::new(|| match font::load_emoji(THEME.font.as_deref()) {
+    Some(bytes) => Font::External {
+        name: "emoji",
+        bytes,
+    },
+    None => Font::Default,
+});
+
+#[derive(Debug)]
+pub struct EmojiEntry {
+    pub emoji: &'static Emoji,
+    pub glyph: String,
+    name: Cow<'static, str>,
+}
+
+impl EmojiEntry {
+    pub fn new(emoji: &'static Emoji, tone: Option<SkinTone>) -> Self {
+        let name = match tone {
+            Some(tone) if emoji.skin_tones => {
+                Cow::Owned(format!("{}: {}", emoji.name, tone.name()))
+            }
+            _ => Cow::Borrowed(emoji.name),
+        };
+
+        EmojiEntry {
+            emoji,
+            glyph: emoji.with_skin_tone(tone),
+            name,
+        }
+    }
+
+    // The character without modifier followed by each skin tone variant
+    pub fn skin_tones(emoji: &'static Emoji) -> Vec<Self> {
+        std::iter::once(None)
+            .chain(SkinTone::ALL.iter().copied().map(Some))
+            .map(|tone| EmojiEntry::new(emoji, tone))
+            .collect()
+    }
+}
+
+impl<'a> AsEntry<'a> for EmojiEntry {
+    fn get_display_name(&self) -> &str {
+        self.name.as_ref()
+    }
+
+    fn get_glyph(&self) -> Option<(&str, Font)> {
+        Some((self.glyph.as_str(), *EMOJI_FONT))
+    }
+
+    fn get_icon(&self) -> Option<IconPath> {
+        None
+    }
+
+    fn get_description(&self) -> Option<Cow<'_, str>> {
+        if self.emoji.keywords.is_empty() {
+            None
+        } else {
+            Some(Cow::Owned(self.emoji.keywords.join(", ")))
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use iced::widget::Row;
+use std::borrow::Cow;
+
+use crate::app::entries::AsEntry;
+use crate::app::style::rows::RowStyles;
+use crate::app::Message;
+use crate::engines::{EngineQuery, QuerySource};
+use crate::icons::IconPath;
+
+impl<'a> AsEntry<'a> for EngineQuery {
+    // Like plugin history, the engine icon is used as the main icon
+    fn get_icon_layout<'b>(
+        &'a self,
+        category_icon: Option<&'a IconPath>,
+        style: &'static RowStyles,
+    ) -> Row<'b, Message>
+    where
+        'b: 'a,
+    {
+        let icon = category_icon.cloned().or_else(|| IconPath::fallback("web"));
+        Row::new().push(Self::build_icon(&style.icon, icon))
+    }
+
+    fn get_display_name(&self) -> &str {
+        self.query.as_str()
+    }
+
+    fn get_description(&self) -> Option<Cow<'_, str>> {
+        match self.source {
+            QuerySource::Input => None,
+            QuerySource::History => Some(Cow::Borrowed("Recent search")),
+            QuerySource::Suggestion => Some(Cow::Borrowed("Suggestion")),
+        }
+    }
+}
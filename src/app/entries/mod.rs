@@ -1,30 +1,99 @@
 use crate::app::style::rows::icon::IconStyle;
-use crate::app::style::rows::RowStyles;
+use crate::app::style::rows::{RowStyles, RowTransition};
+use crate::app::style::scrollable::RowLayout;
 use crate::app::Message;
 use crate::icons::{fallback_icon, Extension, IconPath};
 use crate::THEME;
+use iced::alignment::Horizontal;
 use iced::widget::{Container, Image, Row};
-use iced::{Alignment, Length, Renderer};
+use iced::{Alignment, Font, Length, Renderer};
 use iced_native::row;
 use iced_native::widget::{column, container, text};
 use std::borrow::Cow;
 
+pub(crate) mod bookmark_entry;
+pub(crate) mod calc_entry;
 pub(crate) mod db_entry;
+pub(crate) mod emoji_entry;
+pub(crate) mod engine_entry;
 pub(crate) mod pop_entry;
+pub(crate) mod script_entry;
+pub(crate) mod ssh_entry;
+pub(crate) mod window_entry;
+
+// The selected row, and the previously selected one while the selection transition runs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Highlight {
+    pub selected: Option<usize>,
+    pub previous: Option<usize>,
+    pub progress: f32,
+}
+
+impl From<Option<usize>> for Highlight {
+    fn from(selected: Option<usize>) -> Self {
+        Highlight {
+            selected,
+            previous: None,
+            progress: 1.0,
+        }
+    }
+}
+
+impl Highlight {
+    fn transition(&self, idx: usize) -> Option<RowTransition> {
+        let rows = &THEME.app_container.rows;
+        if self.progress >= 1.0 {
+            None
+        } else if self.selected == Some(idx) {
+            Some(RowTransition {
+                from: &rows.row,
+                to: &rows.row_selected,
+                progress: self.progress,
+            })
+        } else if self.previous == Some(idx) {
+            Some(RowTransition {
+                from: &rows.row_selected,
+                to: &rows.row,
+                progress: self.progress,
+            })
+        } else {
+            None
+        }
+    }
+}
 
 pub(crate) trait AsEntry<'a> {
+    // A row, or a cell when the results are displayed as a grid
+    fn to_item<'b>(
+        &'a self,
+        highlight: Highlight,
+        idx: usize,
+        category_icon: Option<&'a IconPath>,
+        shortcut: Option<usize>,
+    ) -> Container<'b, Message>
+    where
+        'b: 'a,
+    {
+        match THEME.app_container.rows.layout {
+            RowLayout::List => self.to_row(highlight, idx, category_icon, shortcut),
+            RowLayout::Grid => self.to_cell(highlight, idx, category_icon, shortcut),
+        }
+    }
+
     fn to_row<'b>(
         &'a self,
-        selected: Option<usize>,
+        highlight: Highlight,
         idx: usize,
         category_icon: Option<&'a IconPath>,
+        shortcut: Option<usize>,
     ) -> Container<'b, Message>
     where
         'b: 'a,
     {
-        let theme = self.get_style(selected, idx);
+        let theme = self.get_style(highlight.selected, idx);
 
-        let row = if THEME.icon_theme.is_some() {
+        // Entries with a glyph don't need an icon
+        let row = if THEME.icon_theme.is_some() && self.get_glyph().is_none() {
             self.get_icon_layout(category_icon, theme)
         } else {
             Row::new()
@@ -37,17 +106,103 @@ pub(crate) trait AsEntry<'a> {
             // See : https://github.com/iced-rs/iced/pull/1044
             .align_items(Alignment::Start);
 
-        self.as_row(row, theme)
+        let row = self.as_row(row, theme, shortcut);
+        match highlight.transition(idx) {
+            Some(transition) => row.style(iced::theme::Container::Custom(Box::new(transition))),
+            None => row,
+        }
     }
 
-    fn as_row<'b>(&self, row: Row<'b, Message>, theme: &'static RowStyles) -> Container<'b, Message>
+    // Grid cells show the icon above the title, there is no room for the description
+    fn to_cell<'b>(
+        &'a self,
+        highlight: Highlight,
+        idx: usize,
+        category_icon: Option<&'a IconPath>,
+        shortcut: Option<usize>,
+    ) -> Container<'b, Message>
     where
         'b: 'a,
     {
-        let title_row: iced_native::widget::Container<'_, Message, Renderer> = container(
-            iced_native::widget::row(vec![text(self.get_display_name())
+        let theme = self.get_style(highlight.selected, idx);
+        let mut cell: Vec<iced_native::Element<'_, Message, Renderer>> = vec![];
+
+        // Icons are laid out like in rows, some entries show their category icon instead
+        let icon = match self.get_glyph() {
+            Some((glyph, font)) => {
+                Some(container(text(glyph).font(font).size(theme.icon.icon_size)))
+            }
+            None if THEME.icon_theme.is_some() => {
+                Some(container(self.get_icon_layout(category_icon, theme)))
+            }
+            None => None,
+        };
+
+        if let Some(icon) = icon {
+            cell.push(container(icon).width(Length::Fill).center_x().into());
+        }
+
+        cell.push(
+            container(
+                text(self.get_display_name())
+                    .font(theme.title.font.for_text(self.get_display_name()))
+                    .size(theme.title.font_size)
+                    .horizontal_alignment(Horizontal::Center),
+            )
+            .style(iced::theme::Container::Custom(Box::new(&theme.title)))
+            .padding(theme.title.padding.to_iced_padding())
+            .width(Length::Fill)
+            .center_x()
+            .into(),
+        );
+
+        // Quick select badge, like in rows
+        if let Some(shortcut) = shortcut.filter(|_| !theme.hide_shortcut) {
+            cell.push(
+                container(text(shortcut.to_string()).size(theme.shortcut.font_size))
+                    .style(iced::theme::Container::Custom(Box::new(&theme.shortcut)))
+                    .padding(theme.shortcut.padding.to_iced_padding())
+                    .width(Length::Fill)
+                    .center_x()
+                    .into(),
+            );
+        }
+
+        let cell = Container::new(column(cell).spacing(theme.spacing))
+            .style(iced::theme::Container::Custom(Box::new(theme)))
+            .padding(theme.padding.to_iced_padding())
+            .width(Length::Fill)
+            .height(theme.height)
+            .center_x();
+
+        match highlight.transition(idx) {
+            Some(transition) => cell.style(iced::theme::Container::Custom(Box::new(transition))),
+            None => cell,
+        }
+    }
+
+    fn as_row<'b>(
+        &self,
+        row: Row<'b, Message>,
+        theme: &'static RowStyles,
+        shortcut: Option<usize>,
+    ) -> Container<'b, Message>
+    where
+        'b: 'a,
+    {
+        let mut title: Vec<iced_native::Element<'_, Message, Renderer>> = vec![];
+        if let Some((glyph, font)) = self.get_glyph() {
+            title.push(text(glyph).font(font).size(theme.title.font_size).into());
+        }
+        title.push(
+            text(self.get_display_name())
+                .font(theme.title.font.for_text(self.get_display_name()))
                 .size(theme.title.font_size)
-                .into()]),
+                .into(),
+        );
+
+        let title_row: iced_native::widget::Container<'_, Message, Renderer> = container(
+            iced_native::widget::row(title).spacing(theme.spacing),
         )
         .style(iced::theme::Container::Custom(Box::new(
             &theme.title,
@@ -62,7 +217,9 @@ pub(crate) trait AsEntry<'a> {
             iced_native::widget::Container<'_, Message, Renderer>,
         > = self.get_description().map(|description| {
             container(row!(
-                text(description.as_ref()).size(theme.description.font_size)
+                text(description.as_ref())
+                    .font(theme.description.font.for_text(description.as_ref()))
+                    .size(theme.description.font_size)
             ))
             .style(iced::theme::Container::Custom(Box::new(
                 &theme.description,
@@ -81,7 +238,23 @@ pub(crate) trait AsEntry<'a> {
             _ => column,
         };
 
-        Container::new(row.push(column))
+        let row = row.push(column);
+
+        // Quick select badge, activated with `Alt+<digit>`
+        let row = match shortcut {
+            Some(shortcut) if !theme.hide_shortcut => row.push(
+                container(text(shortcut.to_string()).size(theme.shortcut.font_size))
+                    .style(iced::theme::Container::Custom(Box::new(&theme.shortcut)))
+                    .padding(theme.shortcut.padding.to_iced_padding())
+                    .width(theme.shortcut.width)
+                    .height(theme.shortcut.height)
+                    .align_x(theme.shortcut.align_x)
+                    .align_y(theme.shortcut.align_y),
+            ),
+            _ => row,
+        };
+
+        Container::new(row)
             .style(iced::theme::Container::Custom(Box::new(
                 theme,
             )))
@@ -160,8 +333,104 @@ pub(crate) trait AsEntry<'a> {
 
     fn get_display_name(&self) -> &str;
 
+    // A character displayed before the title with its own font, e.g. an emoji
+    fn get_glyph(&self) -> Option<(&str, Font)> {
+        None
+    }
+
     fn get_icon(&self) -> Option<IconPath> {
         unreachable!()
     }
     fn get_description(&self) -> Option<Cow<'_, str>>;
 }
+
+#[cfg(test)]
+mod test {
+    use crate::app::entries::calc_entry::CalcEntry;
+    use crate::app::entries::emoji_entry::EmojiEntry;
+    use crate::app::entries::pop_entry::{PopContextOption, PopSearchResult};
+    use crate::app::entries::{AsEntry, Highlight};
+    use crate::bookmarks::Bookmark;
+    use crate::db::desktop_entry::DesktopEntryEntity;
+    use crate::db::plugin::PluginCommandEntity;
+    use crate::db::web::WebEntity;
+    use crate::emoji;
+    use crate::engines::{EngineQuery, QuerySource};
+    use crate::scripts::ScriptEntry;
+    use crate::ssh::SshHost;
+    use crate::windows::Window;
+    use pop_launcher_toolkit::launcher::{ContextOption, SearchResult};
+    use std::borrow::Cow;
+    use std::path::PathBuf;
+
+    fn render<'a, E: AsEntry<'a>>(entry: &'a E) {
+        let highlight = Highlight::from(Some(0));
+        let _ = entry.to_cell(highlight, 0, None, Some(1));
+        let _ = entry.to_cell(highlight, 1, None, None);
+        let _ = entry.to_row(highlight, 0, None, Some(1));
+    }
+
+    #[test]
+    fn should_render_every_entry_in_grid_layout() {
+        let search_result = SearchResult {
+            id: 0,
+            name: "Firefox".to_string(),
+            description: "Web browser".to_string(),
+            icon: None,
+            category_icon: None,
+            window: None,
+        };
+        let context_option = ContextOption {
+            id: 0,
+            name: "Open in new window".to_string(),
+        };
+
+        render(&DesktopEntryEntity {
+            name: Cow::Borrowed("Firefox"),
+            icon: Some(Cow::Borrowed("firefox")),
+            description: None,
+            path: PathBuf::from("/usr/share/applications/firefox.desktop"),
+            weight: 0,
+        });
+        render(&PluginCommandEntity {
+            query: Cow::Borrowed("ls ~"),
+            weight: 0,
+        });
+        render(&WebEntity {
+            query: Cow::Borrowed("rust"),
+            kind: Cow::Borrowed("ddg"),
+            weight: 0,
+        });
+        render(&EngineQuery {
+            query: "rust".to_string(),
+            source: QuerySource::Suggestion,
+        });
+        render(&CalcEntry::new("1 + 1"));
+        render(&EmojiEntry::new(emoji::search("smile", |_| 0)[0], None));
+        render(&PopSearchResult(&search_result));
+        render(&PopContextOption(&context_option));
+        render(&Bookmark {
+            title: "The Rust Book".to_string(),
+            url: "https://doc.rust-lang.org/book/".to_string(),
+            folder: None,
+            favicon: None,
+        });
+        render(&ScriptEntry {
+            text: "notes.md".to_string(),
+            icon: None,
+            info: None,
+        });
+        render(&SshHost {
+            name: "build".to_string(),
+            hostname: None,
+            user: None,
+            port: None,
+        });
+        render(&Window {
+            id: "4".to_string(),
+            title: "Mozilla Firefox".to_string(),
+            app_id: Some("firefox".to_string()),
+            workspace: None,
+        });
+    }
+}
@@ -1,4 +1,4 @@
-use pop_launcher_toolkit::launcher::SearchResult;
+use pop_launcher_toolkit::launcher::{ContextOption, SearchResult};
 use std::borrow::Cow;
 
 use crate::app::entries::AsEntry;
@@ -7,6 +7,8 @@ use crate::THEME;
 
 pub struct PopSearchResult<'a>(pub &'a SearchResult);
 
+pub struct PopContextOption<'a>(pub &'a ContextOption);
+
 impl<'a> AsEntry<'a> for PopSearchResult<'a> {
     fn get_display_name(&self) -> &str {
         self.0.name.as_str()
@@ -18,7 +20,8 @@ impl<'a> AsEntry<'a> for PopSearchResult<'a> {
                 .0
                 .icon
                 .as_ref()
-                .and_then(|source| IconPath::from_source(source, theme)),
+                .and_then(|source| IconPath::from_source(source, theme))
+                .or_else(|| IconPath::fallback("plugin")),
             _ => None,
         }
     }
@@ -27,3 +30,17 @@ impl<'a> AsEntry<'a> for PopSearchResult<'a> {
         Some(Cow::Borrowed(self.0.description.as_str()))
     }
 }
+
+impl<'a> AsEntry<'a> for PopContextOption<'a> {
+    fn get_display_name(&self) -> &str {
+        self.0.name.as_str()
+    }
+
+    fn get_icon(&self) -> Option<IconPath> {
+        None
+    }
+
+    fn get_description(&self) -> Option<Cow<'_, str>> {
+        None
+    }
+}
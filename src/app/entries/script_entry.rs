@@ -0,0 +1,35 @@
+use std::borrow::Cow;
+
+use crate::app::entries::AsEntry;
+use crate::icons::IconPath;
+use crate::scripts::ScriptEntry;
+use crate::THEME;
+
+impl<'a> AsEntry<'a> for ScriptEntry {
+    fn get_display_name(&self) -> &str {
+        self.text.as_str()
+    }
+
+    fn get_icon(&self) -> Option<IconPath> {
+        self.icon
+            .as_deref()
+            .and_then(script_icon)
+            .or_else(|| IconPath::fallback("script"))
+    }
+
+    fn get_description(&self) -> Option<Cow<'_, str>> {
+        None
+    }
+}
+
+// Scripts may print either an icon name or an absolute path
+pub(crate) fn script_icon(icon: &str) -> Option<IconPath> {
+    if icon.starts_with('/') {
+        return IconPath::from_path(icon, icon.contains("-symbolic"));
+    }
+
+    match &THEME.icon_theme {
+        Some(theme) => IconPath::lookup(icon, theme, THEME.icon_size),
+        _ => None,
+    }
+}
@@ -0,0 +1,24 @@
+use std::borrow::Cow;
+
+use crate::app::entries::AsEntry;
+use crate::icons::IconPath;
+use crate::ssh::SshHost;
+use crate::THEME;
+
+impl<'a> AsEntry<'a> for SshHost {
+    fn get_display_name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn get_icon(&self) -> Option<IconPath> {
+        match &THEME.icon_theme {
+            Some(theme) => IconPath::lookup("network-server", theme, THEME.icon_size)
+                .or_else(|| IconPath::fallback("ssh")),
+            _ => None,
+        }
+    }
+
+    fn get_description(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Owned(self.target()))
+    }
+}
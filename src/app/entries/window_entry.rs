@@ -0,0 +1,34 @@
+use std::borrow::Cow;
+
+use crate::app::entries::AsEntry;
+use crate::icons::IconPath;
+use crate::windows::Window;
+use crate::THEME;
+
+impl<'a> AsEntry<'a> for Window {
+    fn get_display_name(&self) -> &str {
+        self.title.as_str()
+    }
+
+    // app_id and WM_CLASS usually match the application icon name
+    fn get_icon(&self) -> Option<IconPath> {
+        match (&THEME.icon_theme, &self.app_id) {
+            (Some(theme), Some(app_id)) => {
+                IconPath::lookup(&app_id.to_lowercase(), theme, THEME.icon_size)
+            }
+            _ => None,
+        }
+        .or_else(|| IconPath::fallback("window"))
+    }
+
+    fn get_description(&self) -> Option<Cow<'_, str>> {
+        match (&self.app_id, &self.workspace) {
+            (Some(app_id), Some(workspace)) => {
+                Some(Cow::Owned(format!("{app_id} on workspace {workspace}")))
+            }
+            (Some(app_id), None) => Some(Cow::Borrowed(app_id.as_str())),
+            (None, Some(workspace)) => Some(Cow::Owned(format!("workspace {workspace}"))),
+            (None, None) => None,
+        }
+    }
+}
@@ -1,59 +1,77 @@
-use crate::app::entries::pop_entry::PopSearchResult;
-use crate::app::entries::AsEntry;
-use crate::app::mode::ActiveMode;
+use crate::app::animation::exit;
+use crate::app::clipboard::CopyField;
+use crate::app::entries::calc_entry::CalcEntry;
+use crate::app::entries::emoji_entry::EmojiEntry;
+use crate::app::entries::pop_entry::{PopContextOption, PopSearchResult};
+use crate::app::entries::{AsEntry, Highlight};
+use crate::app::mode::{ActiveMode, WINDOW_MANAGER};
 use crate::app::plugin_matchers::Plugin;
-use crate::app::state::{Selection, State};
+use crate::app::preview::{Preview, PreviewContent};
+use crate::app::sections::{Line, Sections};
+use crate::app::state::{ContextMenu, Selection, State};
+use crate::app::style::scrollable::RowLayout;
+use crate::app::subscriptions::icons::IconPipelineSubscription;
 use crate::app::subscriptions::plugin_configs::PluginMatcherSubscription;
 use crate::app::subscriptions::pop_launcher::{PopLauncherSubscription, SubscriptionMessage};
+use crate::app::viewport::{shortcut, shortcut_entry};
+use crate::bookmarks::{self, Bookmark};
 use crate::db::desktop_entry::DesktopEntryEntity;
 use crate::db::plugin::PluginCommandEntity;
 use crate::db::web::WebEntity;
-use crate::font::DEFAULT_FONT;
-use crate::freedesktop::desktop::DesktopEntry;
+use crate::emoji::{self, EmojiAction};
+use crate::engines::{EngineQuery, QuerySource, WebEngine};
+use crate::freedesktop::desktop::{DesktopEntry, DISCRETE_GPU_ENV};
 use crate::icons::IconPath;
-use crate::{font, THEME};
+use crate::scripts::ScriptEntry;
+use crate::ssh::{self, SshHost};
+use crate::windows::Window;
+use crate::{calc, font, THEME};
 use iced::alignment::{Horizontal, Vertical};
 use iced::futures::channel::mpsc::{Sender, TrySendError};
-use iced::keyboard::KeyCode;
-use iced::widget::{Column, Container, Row, Text};
+use iced::keyboard::{KeyCode, Modifiers};
+use iced::widget::{Column, Container, Row, Space, Text};
 use iced::window::PlatformSpecific;
 use iced::{window, Application, Command, Element, Length, Renderer, Settings};
 use iced_native::widget::scrollable::RelativeOffset;
-use iced_native::widget::{column, container, scrollable, text_input};
+use iced_native::widget::{column, container, scrollable, text, text_input};
 use iced_native::{Event, Subscription};
 use iced_style::Theme;
-use log::{debug, trace};
+use log::{debug, error, trace, warn};
 use once_cell::sync::Lazy;
-use pop_launcher_toolkit::launcher::{Request, Response};
-use std::path::Path;
-use std::process::exit;
+use pop_launcher_toolkit::launcher::{GpuPreference, Request, Response, SearchResult};
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
+pub mod animation;
 pub mod cache;
+pub mod clipboard;
 pub mod entries;
 pub mod mode;
+pub mod placement;
 pub mod plugin_matchers;
+pub mod preview;
+pub mod scale;
+pub mod sections;
 pub mod state;
 pub mod style;
 pub mod subscriptions;
+pub mod viewport;
 
 pub fn run() -> iced::Result {
     debug!("Starting Onagre in debug mode");
 
-    let default_font = THEME
-        .font
-        .as_ref()
-        .and_then(|font| font::load(font))
-        .unwrap_or(DEFAULT_FONT);
-
     Onagre::run(Settings {
         id: Some("onagre".to_string()),
         window: window::Settings {
             transparent: true,
-            size: THEME.size,
+            size: scaled_size(THEME.size),
             decorations: false,
             always_on_top: true,
             resizable: false,
-            position: window::Position::Centered,
+            position: placement::POSITION
+                .map(|(x, y)| window::Position::Specific(x, y))
+                .unwrap_or(window::Position::Centered),
             min_size: None,
             max_size: None,
             icon: None,
@@ -64,12 +82,21 @@ pub fn run() -> iced::Result {
         text_multithreading: false,
         antialiasing: true,
         exit_on_close_request: false,
-        default_font: Some(default_font),
+        default_font: Some(*font::DEFAULT),
         flags: (),
         try_opengles_first: true,
     })
 }
 
+// The window size isn't affected by `Application::scale_factor`
+fn scaled_size((width, height): (u32, u32)) -> (u32, u32) {
+    let scale = scale::ui_scale();
+    (
+        (width as f32 * scale).round() as u32,
+        (height as f32 * scale).round() as u32,
+    )
+}
+
 #[derive(Debug)]
 pub struct Onagre<'a> {
     state: State<'a>,
@@ -80,12 +107,27 @@ pub struct Onagre<'a> {
 pub enum Message {
     Loading,
     InputChanged(String),
-    KeyboardEvent(KeyCode),
+    KeyboardEvent(KeyCode, Modifiers),
     SubscriptionResponse(SubscriptionMessage),
+    DebouncedSearch(u64),
+    PreviewLoaded(Vec<PathBuf>, Option<PreviewContent>),
     PluginConfig(Plugin),
+    ScriptEntries(u64, Vec<ScriptEntry>),
+    ScriptActivated(Vec<ScriptEntry>),
+    EngineDebounced(u64),
+    EngineSuggestions(u64, Vec<String>),
+    BookmarksLoaded(Vec<Bookmark>),
+    // An icon finished converting, the rows are rebuilt on the next redraw
+    IconReady,
+    // Animation clock, only running during transitions
+    Tick(Instant),
+    Focused,
     Unfocused,
 }
 
+// Used by the window manager to find the onagre window
+pub const TITLE: &str = "Onagre";
+
 static INPUT_ID: Lazy<text_input::Id> = Lazy::new(text_input::Id::unique);
 static SCROLL_ID: Lazy<scrollable::Id> = Lazy::new(scrollable::Id::unique);
 
@@ -109,95 +151,171 @@ impl Application for Onagre<'_> {
     }
 
     fn title(&self) -> String {
-        "Onagre".to_string()
+        TITLE.to_string()
+    }
+
+    fn scale_factor(&self) -> f64 {
+        f64::from(scale::ui_scale())
     }
 
     fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
-        match message {
+        // The window is fading out, don't launch anything else
+        if animation::is_exiting() {
+            return Command::none();
+        }
+
+        // Only new results, mode changes and the context menu change the rows displayed
+        let new_results = matches!(
+            message,
+            Message::InputChanged(_)
+                | Message::SubscriptionResponse(_)
+                | Message::ScriptEntries(..)
+                | Message::ScriptActivated(_)
+                | Message::EngineSuggestions(..)
+                | Message::BookmarksLoaded(_)
+        );
+        let context_menu = self.state.context_menu.is_some();
+        let mode = self.state.get_active_mode().clone();
+        let entries_len = self.current_entries_len();
+
+        let command = match message {
             Message::Loading => text_input::focus(INPUT_ID.clone()),
             Message::InputChanged(input) => self.on_input_changed(input),
-            Message::KeyboardEvent(event) => self.handle_input(event),
+            Message::KeyboardEvent(event, modifiers) => self.handle_input(event, modifiers),
             Message::SubscriptionResponse(message) => self.on_pop_launcher_message(message),
+            Message::DebouncedSearch(seq) => {
+                if self.state.search.on_debounce_elapsed(seq) {
+                    let value = self.state.get_input();
+                    self.send_search(value)
+                } else {
+                    Command::none()
+                }
+            }
+            Message::Focused => {
+                if !self.state.mapped {
+                    self.state.mapped = true;
+                    placement::float(&self.title());
+                    animation::fade_in(self.title());
+                }
+                Command::none()
+            }
             Message::Unfocused => {
                 if THEME.exit_unfocused {
-                    exit(0);
+                    exit(0)
                 } else {
                     Command::none()
                 }
             }
+            Message::PreviewLoaded(paths, content) => {
+                // Ignore previews loaded for a previous selection
+                if let Some(preview) = &mut self.state.preview {
+                    if preview.paths == paths {
+                        preview.content = content;
+                    }
+                }
+                Command::none()
+            }
+            Message::ScriptEntries(seq, entries) => {
+                if seq == self.state.script_seq {
+                    self.state.script_entries = entries;
+                }
+                Command::none()
+            }
+            // Like rofi, the script prints nothing once it is done with the selected entry
+            Message::ScriptActivated(entries) => {
+                if entries.is_empty() {
+                    return exit(0);
+                }
+
+                self.state.script_seq += 1;
+                self.state.script_entries = entries;
+                self.state.selected = Selection::History(0);
+                self.state.viewport.reset();
+                self.snap()
+            }
+            Message::EngineDebounced(seq) => match self.state.get_active_mode() {
+                ActiveMode::WebEngine { name, .. } if seq == self.state.engine_seq => {
+                    let name = name.clone();
+                    self.engine_suggestions(&name, seq)
+                }
+                _ => Command::none(),
+            },
+            Message::EngineSuggestions(seq, suggestions) => {
+                if seq == self.state.engine_seq {
+                    for query in suggestions {
+                        if !self
+                            .state
+                            .engine_queries
+                            .iter()
+                            .any(|row| row.query == query)
+                        {
+                            self.state.engine_queries.push(EngineQuery {
+                                query,
+                                source: QuerySource::Suggestion,
+                            });
+                        }
+                    }
+                }
+                Command::none()
+            }
+            Message::BookmarksLoaded(bookmarks) => {
+                self.state.bookmarks_loading = false;
+                let query = &self.state.input_value.pop_query;
+                self.state
+                    .bookmarks
+                    .rank(|| bookmarks, |bookmark| bookmark.score(query));
+                Command::none()
+            }
+            Message::IconReady => Command::none(),
+            Message::Tick(now) => {
+                if self.state.animations.tick(now) {
+                    scrollable::snap_to(SCROLL_ID.clone(), RelativeOffset::START)
+                } else {
+                    self.scroll_step()
+                }
+            }
             Message::PluginConfig(plugin) => {
                 self.state
                     .plugin_matchers
                     .insert(plugin.name.clone(), plugin);
+                // Section labels come from the plugin configurations
+                self.update_sections();
                 Command::none()
             }
+        };
+
+        let rows_changed = new_results
+            || context_menu != self.state.context_menu.is_some()
+            || mode != *self.state.get_active_mode()
+            || entries_len != self.current_entries_len();
+
+        if rows_changed {
+            self.update_sections();
+            Command::batch(vec![command, self.fit_to_rows()])
+        } else {
+            command
         }
     }
 
     fn view(&self) -> Element<'_, Self::Message, Renderer<Self::Theme>> {
-        // Build rows from current mode search entries
-        let selected = self.selected();
-        let rows = match &self.state.get_active_mode() {
-            ActiveMode::Plugin {
-                plugin_name,
-                history,
-                ..
-            } if *history => {
-                let icon = self.state.plugin_matchers.get_plugin_icon(plugin_name);
-                self.state
-                    .cache
-                    .plugin_history(plugin_name)
-                    .iter()
-                    .enumerate()
-                    .map(|(idx, entry)| entry.to_row(selected, idx, icon.as_ref()).into())
-                    .collect()
-            }
-            ActiveMode::Web { modifier, .. } => {
-                let icon = self.state.plugin_matchers.get_plugin_icon("web");
-                self.state
-                    .cache
-                    .web_history(modifier)
-                    .iter()
-                    .enumerate()
-                    .map(|(idx, entry)| entry.to_row(selected, idx, icon.as_ref()).into())
-                    .collect()
-            }
-            ActiveMode::History => {
-                let icon = self
-                    .state
-                    .plugin_matchers
-                    .get_plugin_icon("desktop_entries");
-                self.state
-                    .cache
-                    .de_history()
-                    .iter()
-                    .enumerate()
-                    .map(|(idx, entry)| entry.to_row(selected, idx, icon.as_ref()).into())
-                    .collect()
-            }
-            _ => self
-                .state
-                .pop_search
-                .iter()
-                .map(|entry| {
-                    let icon = match &THEME.icon_theme {
-                        Some(theme) => entry
-                            .category_icon
-                            .as_ref()
-                            .and_then(|source| IconPath::from_source(source, theme)),
-                        _ => None,
-                    };
-
-                    PopSearchResult(entry)
-                        .to_row(selected, entry.id as usize, icon.as_ref())
-                        .into()
-                })
-                .collect(),
+        // Build rows from the context menu or the current mode search entries
+        let rows = match &self.state.context_menu {
+            Some(context_menu) => Self::context_menu_rows(context_menu),
+            None => match self.empty_state() {
+                Some(empty_state) => vec![empty_state],
+                None => self.search_rows(),
+            },
+        };
+
+        // Scrollable element containing the rows, search results are laid out in cells
+        // with the grid layout
+        let rows = match (&self.state.context_menu, THEME.app_container.rows.layout) {
+            (None, RowLayout::Grid) => Self::grid(rows, self.state.viewport.columns),
+            _ => column(rows),
         };
 
-        // Scrollable element containing the rows
         let scrollable =
-            scrollable(column(rows))
+            scrollable(rows)
                 .id(SCROLL_ID.clone())
                 .style(iced::theme::Scrollable::Custom(Box::new(
                     THEME.scrollable(),
@@ -209,11 +327,44 @@ impl Application for Onagre<'_> {
             )))
             .padding(THEME.app_container.rows.padding.to_iced_padding())
             .width(THEME.app_container.rows.width)
-            .height(THEME.app_container.rows.height); // TODO: add this to stylesheet
+            .height(THEME.rows_height()); // TODO: add this to stylesheet
 
-        let text_input = text_input("Search", &self.state.input_value.input_display)
+        // Display the preview pane next to the results when enabled in the theme
+        let results: Element<'_, Message> = match THEME.preview() {
+            Some(preview_style) => {
+                let preview = match &self.state.preview {
+                    Some(preview) => preview.view(preview_style),
+                    None => Container::new(Column::new()),
+                };
+
+                let preview = preview
+                    .style(iced::theme::Container::Custom(Box::new(preview_style)))
+                    .padding(preview_style.padding.to_iced_padding())
+                    .width(preview_style.width)
+                    .height(preview_style.height)
+                    .align_x(preview_style.align_x)
+                    .align_y(preview_style.align_y);
+
+                Row::new()
+                    .push(scrollable)
+                    .push(preview)
+                    .width(Length::Fill)
+                    .height(THEME.rows_height())
+                    .into()
+            }
+            None => scrollable.into(),
+        };
+
+        let input_font = THEME
+            .search_input()
+            .font
+            .for_text(&self.state.input_value.input_display);
+
+        let placeholder = self.placeholder();
+        let text_input = text_input(&placeholder, &self.state.input_value.input_display)
             .on_input(Message::InputChanged)
             .id(INPUT_ID.clone())
+            .font(input_font)
             .style(iced::theme::TextInput::Custom(Box::new(
                 THEME.search_input(),
             )))
@@ -221,13 +372,49 @@ impl Application for Onagre<'_> {
             .width(THEME.search_input().text_width)
             .size(THEME.search_input().font_size);
 
-        let search_input = container(text_input)
+        let search_input = if self.state.search.is_searching() {
+            let indicator = Text::new("searching…")
+                .font(input_font)
+                .size(THEME.search_input().font_size)
+                .style(iced::theme::Text::Color(
+                    THEME.search_input().placeholder_color.into(),
+                ));
+
+            container(
+                Row::new()
+                    .push(text_input)
+                    .push(indicator)
+                    .align_items(iced_core::Alignment::Center),
+            )
+        } else {
+            container(text_input)
+        };
+
+        let search_input = search_input
             .width(THEME.search_input().width)
             .height(THEME.search_input().height)
             .align_x(THEME.search_input().align_x)
             .align_y(THEME.search_input().align_y);
 
         let search_bar = Row::new().width(Length::Fill).height(Length::Fill);
+        let search_bar = match &THEME.prompt {
+            Some(prompt) => search_bar
+                .push(
+                    container(
+                        Text::new(prompt)
+                            .font(THEME.search_input().font.for_text(prompt))
+                            .size(THEME.search_input().font_size)
+                            .style(iced::theme::Text::Color(
+                                THEME.search_input().value_color.into(),
+                            )),
+                    )
+                    .padding(THEME.search_input().padding.to_iced_padding())
+                    .height(THEME.search_input().height)
+                    .align_y(THEME.search_input().align_y),
+                )
+                .spacing(THEME.search().spacing),
+            None => search_bar,
+        };
         // Either plugin_hint is enabled and we try to display it
         // Or we display the normal search input
         let search_bar = match THEME.plugin_hint() {
@@ -235,6 +422,11 @@ impl Application for Onagre<'_> {
             Some(plugin_hint_style) => if !self.state.input_value.modifier_display.is_empty() {
                 let plugin_hint = Container::new(
                     Text::new(&self.state.input_value.modifier_display)
+                        .font(
+                            plugin_hint_style
+                                .font
+                                .for_text(&self.state.input_value.modifier_display),
+                        )
                         .vertical_alignment(Vertical::Center)
                         .horizontal_alignment(Horizontal::Center)
                         .size(plugin_hint_style.font_size),
@@ -259,12 +451,12 @@ impl Application for Onagre<'_> {
             .align_y(THEME.search().align_y)
             .padding(THEME.search().padding.to_iced_padding())
             .width(THEME.search().width)
-            .height(THEME.search().height);
+            .height(THEME.search_height());
 
         let app_container = Container::new(
             Column::new()
                 .push(search_bar)
-                .push(scrollable)
+                .push(results)
                 .align_items(iced_core::Alignment::Start),
         )
         .padding(THEME.app().padding.to_iced_padding())
@@ -287,12 +479,385 @@ impl Application for Onagre<'_> {
         let keyboard_event = Onagre::keyboard_event();
         let pop_launcher = PopLauncherSubscription::create().map(Message::SubscriptionResponse);
         let matchers = PluginMatcherSubscription::create().map(Message::PluginConfig);
-        let subs = vec![keyboard_event, pop_launcher, matchers];
+        let icons = IconPipelineSubscription::create().map(|()| Message::IconReady);
+        let mut subs = vec![keyboard_event, pop_launcher, matchers, icons];
+        if self.state.animations.is_running() {
+            subs.push(iced::time::every(animation::FRAME).map(Message::Tick));
+        }
+
         iced::Subscription::batch(subs)
     }
 }
 
 impl Onagre<'_> {
+    // Only the rows visible in the viewport are built
+    fn search_rows(&self) -> Vec<Element<'_, Message>> {
+        let highlight = self.highlight();
+        let sections = &self.state.sections;
+        let lines = sections.lines(self.current_entries_len());
+        let visible = match self.state.animations.scroll_position() {
+            Some((from, _)) => self.state.viewport.span(from, lines),
+            None => self.state.viewport.range(lines),
+        };
+        let entries = sections.entries(visible.clone());
+        let offset = entries.start;
+        let count = entries.len();
+
+        match &self.state.get_active_mode() {
+            ActiveMode::Plugin {
+                plugin_name,
+                history,
+                ..
+            } if *history => {
+                let icon = self.state.plugin_matchers.get_plugin_icon(plugin_name);
+                self.state
+                    .cache
+                    .plugin_history(plugin_name)
+                    .iter()
+                    .enumerate()
+                    .skip(offset)
+                    .take(count)
+                    .map(|(idx, entry)| {
+                        entry
+                            .to_item(highlight, idx, icon.as_ref(), shortcut(idx - offset))
+                            .into()
+                    })
+                    .collect()
+            }
+            ActiveMode::Web { modifier, .. } => {
+                let icon = self.state.plugin_matchers.get_plugin_icon("web");
+                self.state
+                    .cache
+                    .web_history(modifier)
+                    .iter()
+                    .enumerate()
+                    .skip(offset)
+                    .take(count)
+                    .map(|(idx, entry)| {
+                        entry
+                            .to_item(highlight, idx, icon.as_ref(), shortcut(idx - offset))
+                            .into()
+                    })
+                    .collect()
+            }
+            ActiveMode::Calc => self
+                .state
+                .calc
+                .iter()
+                .map(|entry| entry.to_item(highlight, 0, None, shortcut(0)).into())
+                .collect(),
+            ActiveMode::Windows => self
+                .state
+                .windows
+                .iter()
+                .enumerate()
+                .skip(offset)
+                .take(count)
+                .map(|(idx, window)| {
+                    window
+                        .to_item(highlight, idx, None, shortcut(idx - offset))
+                        .into()
+                })
+                .collect(),
+            ActiveMode::Ssh => self
+                .state
+                .ssh_hosts
+                .iter()
+                .enumerate()
+                .skip(offset)
+                .take(count)
+                .map(|(idx, host)| {
+                    host.to_item(highlight, idx, None, shortcut(idx - offset))
+                        .into()
+                })
+                .collect(),
+            ActiveMode::Emoji => self
+                .state
+                .emoji
+                .iter()
+                .enumerate()
+                .skip(offset)
+                .take(count)
+                .map(|(idx, entry)| {
+                    entry
+                        .to_item(highlight, idx, None, shortcut(idx - offset))
+                        .into()
+                })
+                .collect(),
+            ActiveMode::Bookmarks => self
+                .state
+                .bookmarks
+                .iter()
+                .enumerate()
+                .skip(offset)
+                .take(count)
+                .map(|(idx, bookmark)| {
+                    bookmark
+                        .to_item(highlight, idx, None, shortcut(idx - offset))
+                        .into()
+                })
+                .collect(),
+            ActiveMode::WebEngine { name, .. } => {
+                let icon = self.state.plugin_matchers.get_plugin_icon(name);
+                self.state
+                    .engine_queries
+                    .iter()
+                    .enumerate()
+                    .skip(offset)
+                    .take(count)
+                    .map(|(idx, entry)| {
+                        entry
+                            .to_item(highlight, idx, icon.as_ref(), shortcut(idx - offset))
+                            .into()
+                    })
+                    .collect()
+            }
+            ActiveMode::Script { name, .. } => {
+                let icon = self.state.plugin_matchers.get_plugin_icon(name);
+                self.state
+                    .script_entries
+                    .iter()
+                    .enumerate()
+                    .skip(offset)
+                    .take(count)
+                    .map(|(idx, entry)| {
+                        entry
+                            .to_item(highlight, idx, icon.as_ref(), shortcut(idx - offset))
+                            .into()
+                    })
+                    .collect()
+            }
+            ActiveMode::History => {
+                let icon = self
+                    .state
+                    .plugin_matchers
+                    .get_plugin_icon("desktop_entries");
+                self.state
+                    .cache
+                    .de_history()
+                    .iter()
+                    .enumerate()
+                    .skip(offset)
+                    .take(count)
+                    .map(|(idx, entry)| {
+                        entry
+                            .to_item(highlight, idx, icon.as_ref(), shortcut(idx - offset))
+                            .into()
+                    })
+                    .collect()
+            }
+            _ => visible
+                .filter_map(|line| match sections.line(line) {
+                    Line::Header(label) => Some(Self::section_header(label)),
+                    Line::Entry(idx) => {
+                        let entry = match self.pop_index(idx) {
+                            Some(pop_idx) => self.state.pop_search.get(pop_idx)?,
+                            None => {
+                                let calc = self.state.calc.as_ref()?;
+                                let row =
+                                    calc.to_item(highlight, idx, None, shortcut(idx - offset));
+                                return Some(row.into());
+                            }
+                        };
+                        let icon = match &THEME.icon_theme {
+                            Some(theme) => entry
+                                .category_icon
+                                .as_ref()
+                                .and_then(|source| IconPath::from_source(source, theme)),
+                            _ => None,
+                        };
+
+                        let row = PopSearchResult(entry).to_item(
+                            highlight,
+                            idx,
+                            icon.as_ref(),
+                            shortcut(idx - offset),
+                        );
+
+                        Some(row.into())
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    // Placeholder from the theme, looked up by mode then by plugin prefix,
+    // or named after the plugin
+    fn placeholder(&self) -> String {
+        let mode = self.state.get_active_mode();
+        let help = match mode {
+            ActiveMode::Plugin { plugin_name, .. } => self
+                .state
+                .plugin_matchers
+                .inner
+                .get(plugin_name)
+                .and_then(|plugin| plugin.help.as_deref()),
+            _ => None,
+        };
+
+        let placeholder = THEME
+            .placeholder(mode.name())
+            .or_else(|| help.and_then(|help| THEME.placeholder(help)));
+
+        match (placeholder, mode) {
+            (Some(placeholder), _) => placeholder.to_string(),
+            (None, ActiveMode::History | ActiveMode::DesktopEntry) => "Search apps…".to_string(),
+            (None, ActiveMode::Calc) => "Calculate…".to_string(),
+            (None, ActiveMode::Plugin { plugin_name, .. }) if help.is_some() => {
+                format!(
+                    "Search {}…",
+                    sections::plugin_label(plugin_name).to_lowercase()
+                )
+            }
+            (None, _) => "Search".to_string(),
+        }
+    }
+
+    // The query matches nothing and the search is over
+    fn is_empty_state(&self) -> bool {
+        !self.state.input_value.input_display.trim().is_empty()
+            && !self.state.search.is_searching()
+            && !self.state.bookmarks_loading
+            && self.current_entries_len() == 0
+    }
+
+    // Replaces the results when the query matches nothing
+    fn empty_state(&self) -> Option<Element<'_, Message>> {
+        if !self.is_empty_state() {
+            return None;
+        }
+
+        let query = &self.state.input_value.input_display;
+        let style = &THEME.app_container.rows.row;
+        let message = THEME.empty_message(query);
+        let mut lines: Vec<Element<'_, Message>> = vec![container(
+            text(&message)
+                .font(style.title.font.for_text(&message))
+                .size(style.title.font_size),
+        )
+        .style(iced::theme::Container::Custom(Box::new(&style.title)))
+        .padding(style.title.padding.to_iced_padding())
+        .width(Length::Fill)
+        .into()];
+
+        if let Some((engine, _)) = self.web_suggestion() {
+            let suggestion = format!("Press Enter to search {} instead", engine.name);
+            lines.push(
+                container(text(suggestion).size(style.description.font_size))
+                    .style(iced::theme::Container::Custom(Box::new(&style.description)))
+                    .padding(style.description.padding.to_iced_padding())
+                    .width(Length::Fill)
+                    .into(),
+            );
+        }
+
+        let empty_state = Container::new(column(lines).spacing(style.spacing))
+            .style(iced::theme::Container::Custom(Box::new(style)))
+            .padding(style.padding.to_iced_padding())
+            .width(Length::Fill);
+
+        Some(empty_state.into())
+    }
+
+    // Applications matching nothing are searched with the first web engine of the theme
+    fn web_suggestion(&self) -> Option<(&'static WebEngine, String)> {
+        let engine = THEME.web_engines.first()?;
+        match self.state.get_active_mode() {
+            ActiveMode::DesktopEntry => Some((
+                engine,
+                format!("{}{}", engine.prefix, self.state.input_value.input_display),
+            )),
+            _ => None,
+        }
+    }
+
+    // Results are only grouped in the default mode, where several plugins answer
+    fn build_sections(&self) -> Sections {
+        let rows = &THEME.app_container.rows;
+        if !rows.group_results
+            || rows.layout == RowLayout::Grid
+            || self.state.get_active_mode() != &ActiveMode::DesktopEntry
+        {
+            return Sections::default();
+        }
+
+        let calc = self
+            .state
+            .calc
+            .iter()
+            .map(|_| sections::plugin_label("calc"));
+        Sections::new(
+            calc.chain(
+                self.state
+                    .pop_search
+                    .iter()
+                    .map(|result| sections::label(result, &self.state.plugin_matchers)),
+            )
+            .take(self.current_entries_len()),
+        )
+    }
+
+    // Sections only change with the results and the active mode, they are rebuilt once
+    // and not for each row looking up its section
+    fn update_sections(&mut self) {
+        self.state.sections = self.build_sections();
+    }
+
+    fn section_header<'a>(label: &str) -> Element<'a, Message> {
+        let style = &THEME.app_container.rows.section_header;
+        container(
+            text(label)
+                .font(style.font.for_text(label))
+                .size(style.font_size),
+        )
+        .style(iced::theme::Container::Custom(Box::new(style)))
+        .padding(style.padding.to_iced_padding())
+        .width(style.width)
+        .height(style.height)
+        .align_x(style.align_x)
+        .align_y(style.align_y)
+        .into()
+    }
+
+    // Split the cells into rows, the last row is padded so cells keep the same width
+    fn grid(cells: Vec<Element<'_, Message>>, columns: usize) -> Column<'_, Message> {
+        let mut rows = Column::new();
+        let mut row = Row::new();
+        let mut len = 0;
+        for cell in cells {
+            row = row.push(cell);
+            len += 1;
+            if len == columns {
+                rows = rows.push(row);
+                row = Row::new();
+                len = 0;
+            }
+        }
+
+        if len > 0 {
+            for _ in len..columns {
+                row = row.push(Space::with_width(Length::Fill));
+            }
+            rows = rows.push(row);
+        }
+
+        rows
+    }
+
+    fn context_menu_rows(context_menu: &ContextMenu) -> Vec<Element<'_, Message>> {
+        let highlight = Highlight::from(Some(context_menu.selected));
+        context_menu
+            .options
+            .iter()
+            .enumerate()
+            .map(|(idx, option)| {
+                PopContextOption(option)
+                    .to_row(highlight, idx, None, shortcut(idx))
+                    .into()
+            })
+            .collect()
+    }
+
     // Only call this if we are using entries from the database
     // in order to re-ask pop-launcher for the exact same entry
     fn current_entry(&self) -> Option<String> {
@@ -336,77 +901,574 @@ impl Onagre<'_> {
         }
     }
 
-    fn on_input_changed(&mut self, input: String) -> Command<Message> {
-        self.state.set_input(&input);
-        self.state.selected = match self.state.get_active_mode() {
-            // For those mode first line is unselected on change
-            // We want to issue a pop-launcher search request to get the query at index 0 in
-            // the next search response, then activate it
-            ActiveMode::Web { .. } | ActiveMode::History => Selection::Reset,
-            ActiveMode::Plugin { history, .. } if *history => Selection::Reset,
-            _ => Selection::PopLauncher(0),
+    // Copy the selected row to the clipboard instead of activating it
+    fn copy_selected(&mut self) -> Command<Message> {
+        let mode = self.state.get_active_mode();
+        let text = match THEME.copy_field(mode.name()) {
+            CopyField::Query => Some(self.state.input_value.pop_query.clone()),
+            field => self.selected_text(field),
         };
 
-        let _: iced::Command<Message> =
-            scrollable::snap_to(SCROLL_ID.clone(), RelativeOffset::START);
-
-        match &self.state.get_active_mode() {
-            ActiveMode::History => {}
-            _ => {
-                let value = self.state.get_input();
+        match text {
+            Some(text) => Self::copy_and_exit(text),
+            None => Command::none(),
+        }
+    }
 
-                self.pop_request(Request::Search(value))
-                    .expect("Unable to send search request to pop-launcher")
+    fn copy_and_exit(text: String) -> Command<Message> {
+        match clipboard::copy(&text) {
+            Ok(()) => exit(0),
+            // Fallback to the window clipboard, it is only served while onagre is running
+            Err(err) => {
+                debug!(
+                    "Failed to copy with an external command: {err}, using the window clipboard"
+                );
+                iced::clipboard::write(text)
             }
         }
-
-        text_input::focus(INPUT_ID.clone())
     }
 
-    fn run_command<P: AsRef<Path>>(&self, desktop_entry_path: P) -> Command<Message> {
-        let desktop_entry = DesktopEntry::from_path(&desktop_entry_path).unwrap();
-
-        DesktopEntryEntity::persist(
-            &desktop_entry,
-            desktop_entry_path.as_ref(),
-            &self.state.cache.db,
-        );
-
-        let argv = shell_words::split(&desktop_entry.exec);
-        let args = argv.unwrap();
-        let args = args
-            .iter()
+    fn selected_text(&self, field: CopyField) -> Option<String> {
+        match (&self.state.selected, self.state.get_active_mode()) {
+            (_, ActiveMode::Calc) => self
+                .state
+                .calc
+                .as_ref()
+                .map(|entry| Self::entry_text(entry, field)),
+            (_, ActiveMode::Windows) => self
+                .selected_window()
+                .map(|window| Self::entry_text(window, field)),
+            (_, ActiveMode::Ssh) => self
+                .selected_ssh_host()
+                .map(|host| Self::entry_text(host, field)),
+            (_, ActiveMode::Emoji) => self
+                .selected_emoji()
+                .map(|entry| Self::entry_text(entry, field)),
+            (_, ActiveMode::Bookmarks) => self
+                .selected_bookmark()
+                .map(|bookmark| Self::entry_text(bookmark, field)),
+            (_, ActiveMode::Script { .. }) => self
+                .selected_script_entry()
+                .map(|entry| Self::entry_text(entry, field)),
+            (_, ActiveMode::WebEngine { .. }) => self
+                .selected_engine_query()
+                .map(|entry| Self::entry_text(entry, field)),
+            (Selection::History(idx), ActiveMode::History) => self
+                .state
+                .cache
+                .de_history()
+                .get(*idx)
+                .map(|entry| Self::entry_text(entry, field)),
+            (Selection::History(idx), ActiveMode::Plugin { plugin_name, .. }) => self
+                .state
+                .cache
+                .plugin_history(plugin_name)
+                .get(*idx)
+                .map(|entry| Self::entry_text(entry, field)),
+            (Selection::History(idx), ActiveMode::Web { modifier, .. }) => self
+                .state
+                .cache
+                .web_history(modifier)
+                .get(*idx)
+                .map(|entry| Self::entry_text(entry, field)),
+            (Selection::History(_), _) => None,
+            // Nothing selected in history modes, the first pop-launcher result holds the user query
+            (Selection::Reset, ActiveMode::History) => None,
+            (Selection::Reset, _) => self
+                .state
+                .pop_search
+                .get(0)
+                .map(|entry| Self::entry_text(&PopSearchResult(entry), field)),
+            (Selection::PopLauncher(idx), _) => match self.pop_index(*idx) {
+                Some(idx) => self
+                    .state
+                    .pop_search
+                    .get(idx)
+                    .map(|entry| Self::entry_text(&PopSearchResult(entry), field)),
+                None => self
+                    .state
+                    .calc
+                    .as_ref()
+                    .map(|entry| Self::entry_text(entry, field)),
+            },
+        }
+    }
+
+    fn entry_text<'a>(entry: &impl AsEntry<'a>, field: CopyField) -> String {
+        let name = entry.get_display_name();
+        match field {
+            CopyField::Description => entry
+                .get_description()
+                .filter(|description| !description.is_empty())
+                .map(|description| description.to_string())
+                .unwrap_or_else(|| name.to_string()),
+            _ => name.to_string(),
+        }
+    }
+
+    fn on_input_changed(&mut self, input: String) -> Command<Message> {
+        self.state.set_input(&input);
+        self.state.context_menu = None;
+        self.state.selected = match self.state.get_active_mode() {
+            // For those mode first line is unselected on change
+            // We want to issue a pop-launcher search request to get the query at index 0 in
+            // the next search response, then activate it
+            ActiveMode::Web { .. } | ActiveMode::History => Selection::Reset,
+            ActiveMode::Plugin { history, .. } if *history => Selection::Reset,
+            // Those rows do not come from pop-launcher
+            mode if mode.is_builtin() => Selection::History(0),
+            _ => Selection::PopLauncher(0),
+        };
+
+        self.state.viewport.reset();
+
+        // Built-in modes are handled in process, no need to query pop-launcher
+        match self.state.get_active_mode() {
+            ActiveMode::Calc => {
+                self.state.calc = Some(CalcEntry::new(&self.state.input_value.pop_query));
+                return text_input::focus(INPUT_ID.clone());
+            }
+            ActiveMode::Windows => {
+                self.filter_windows();
+                return text_input::focus(INPUT_ID.clone());
+            }
+            ActiveMode::Ssh => {
+                self.filter_ssh_hosts();
+                return text_input::focus(INPUT_ID.clone());
+            }
+            ActiveMode::Emoji => {
+                self.filter_emoji();
+                return text_input::focus(INPUT_ID.clone());
+            }
+            ActiveMode::Bookmarks => {
+                return Command::batch(vec![
+                    text_input::focus(INPUT_ID.clone()),
+                    self.filter_bookmarks(),
+                ]);
+            }
+            ActiveMode::Script { name, .. } => {
+                let name = name.clone();
+                return Command::batch(vec![
+                    text_input::focus(INPUT_ID.clone()),
+                    self.run_script(&name),
+                ]);
+            }
+            ActiveMode::WebEngine { name, .. } => {
+                let name = name.clone();
+                return Command::batch(vec![
+                    text_input::focus(INPUT_ID.clone()),
+                    self.search_engine(&name),
+                ]);
+            }
+            _ => {}
+        }
+
+        // An expression typed without `=` is evaluated in a row above the desktop entries
+        self.state.calc = match self.state.get_active_mode() {
+            ActiveMode::DesktopEntry if calc::is_expression(&self.state.input_value.pop_query) => {
+                Some(CalcEntry::new(&self.state.input_value.pop_query))
+            }
+            _ => None,
+        };
+        self.update_sections();
+
+        if self.state.get_active_mode() == &ActiveMode::History {
+            return text_input::focus(INPUT_ID.clone());
+        }
+
+        if let Some(delay) = self.search_debounce() {
+            let seq = self.state.search.debounce();
+            return Command::batch(vec![
+                text_input::focus(INPUT_ID.clone()),
+                Command::perform(tokio::time::sleep(delay), move |()| {
+                    Message::DebouncedSearch(seq)
+                }),
+            ]);
+        }
+
+        let value = self.state.get_input();
+        Command::batch(vec![
+            text_input::focus(INPUT_ID.clone()),
+            self.send_search(value),
+        ])
+    }
+
+    fn filter_windows(&mut self) {
+        let query = &self.state.input_value.pop_query;
+        let load = || match WINDOW_MANAGER.as_ref() {
+            Some(window_manager) => window_manager.windows().unwrap_or_else(|err| {
+                warn!("Failed to list windows: {err}");
+                vec![]
+            }),
+            None => vec![],
+        };
+
+        self.state
+            .windows
+            .filter(load, |window| window.matches(query));
+    }
+
+    // Resize the window when the number of rows changes in `--height: auto` mode
+    fn fit_to_rows(&mut self) -> Command<Message> {
+        let row_height = THEME.row_height(false);
+        let rows = &THEME.app_container.rows;
+        let lines = match &self.state.context_menu {
+            Some(context_menu) => vec![row_height; context_menu.options.len()],
+            None if self.is_empty_state() => vec![row_height],
+            None if rows.layout == RowLayout::Grid => {
+                let columns = self.state.viewport.columns;
+                vec![row_height; (self.current_entries_len() + columns - 1) / columns]
+            }
+            None => {
+                let sections = &self.state.sections;
+                (0..sections.lines(self.current_entries_len()))
+                    .take(THEME.visible_rows())
+                    .map(|line| match sections.line(line) {
+                        Line::Header(_) => rows.section_header.estimated_height(),
+                        Line::Entry(idx) => THEME.row_height(self.has_description(idx)),
+                    })
+                    .collect()
+            }
+        };
+
+        match THEME.auto_height(&lines) {
+            Some(height) if self.state.window_height != Some(height) => {
+                self.state.window_height = Some(height);
+                let (width, height) = scaled_size((THEME.size.0, height));
+                window::resize(width, height)
+            }
+            _ => Command::none(),
+        }
+    }
+
+    // Whether the entry at `idx` displays a description line
+    fn has_description(&self, idx: usize) -> bool {
+        fn described<'a, E: AsEntry<'a>>(entry: Option<&E>) -> bool {
+            matches!(
+                entry.and_then(|entry| entry.get_description()),
+                Some(description) if !description.is_empty()
+            )
+        }
+
+        match self.state.get_active_mode() {
+            ActiveMode::Plugin {
+                plugin_name,
+                history,
+                ..
+            } if *history => described(self.state.cache.plugin_history(plugin_name).get(idx)),
+            ActiveMode::History => described(self.state.cache.de_history().get(idx)),
+            ActiveMode::Web { modifier, .. } => {
+                described(self.state.cache.web_history(modifier).get(idx))
+            }
+            ActiveMode::Calc => described(self.state.calc.as_ref()),
+            ActiveMode::Windows => described(self.state.windows.get(idx)),
+            ActiveMode::Ssh => described(self.state.ssh_hosts.get(idx)),
+            ActiveMode::Emoji => described(self.state.emoji.get(idx)),
+            ActiveMode::Bookmarks => described(self.state.bookmarks.get(idx)),
+            ActiveMode::Script { .. } => described(self.state.script_entries.get(idx)),
+            ActiveMode::WebEngine { .. } => described(self.state.engine_queries.get(idx)),
+            _ => match self.pop_index(idx) {
+                Some(idx) => {
+                    described(self.state.pop_search.get(idx).map(PopSearchResult).as_ref())
+                }
+                None => described(self.state.calc.as_ref()),
+            },
+        }
+    }
+
+    fn selected_window(&self) -> Option<&Window> {
+        self.state.windows.get(self.selected()?)
+    }
+
+    // Hosts are ranked by how often they were connected to from onagre
+    fn filter_ssh_hosts(&mut self) {
+        let query = &self.state.input_value.pop_query;
+        let history = self.state.cache.plugin_history(ssh::COLLECTION);
+        let load = || {
+            let mut hosts = ssh::hosts();
+            hosts.sort_by_key(|host| {
+                Reverse(
+                    history
+                        .iter()
+                        .find(|entry| entry.query == host.name)
+                        .map_or(0, |entry| entry.weight),
+                )
+            });
+            hosts
+        };
+
+        self.state
+            .ssh_hosts
+            .filter(load, |host| host.matches(query));
+    }
+
+    fn selected_ssh_host(&self) -> Option<&SshHost> {
+        self.state.ssh_hosts.get(self.selected()?)
+    }
+
+    // Frequently picked characters are listed first
+    fn filter_emoji(&mut self) {
+        let history = self.state.cache.plugin_history(emoji::COLLECTION);
+        self.state.emoji = emoji::search(&self.state.input_value.pop_query, |emoji| {
+            history
+                .iter()
+                .find(|entry| entry.query == emoji.glyph)
+                .map_or(0, |entry| entry.weight)
+        })
+        .into_iter()
+        .map(|emoji| EmojiEntry::new(emoji, THEME.skin_tone))
+        .collect();
+    }
+
+    fn selected_emoji(&self) -> Option<&EmojiEntry> {
+        self.state.emoji.get(self.selected()?)
+    }
+
+    // Browser databases are copied and queried off the UI thread the first time
+    fn filter_bookmarks(&mut self) -> Command<Message> {
+        if !self.state.bookmarks.is_loaded() {
+            if self.state.bookmarks_loading {
+                return Command::none();
+            }
+
+            self.state.bookmarks_loading = true;
+            let load = async {
+                tokio::task::spawn_blocking(bookmarks::bookmarks)
+                    .await
+                    .unwrap_or_default()
+            };
+            return Command::perform(load, Message::BookmarksLoaded);
+        }
+
+        let query = &self.state.input_value.pop_query;
+        self.state
+            .bookmarks
+            .rank(Vec::new, |bookmark| bookmark.score(query));
+        Command::none()
+    }
+
+    fn selected_bookmark(&self) -> Option<&Bookmark> {
+        self.state.bookmarks.get(self.selected()?)
+    }
+
+    // Replace the rows with the skin tone variants of the selected emoji
+    fn show_skin_tones(&mut self) -> Command<Message> {
+        match self.selected_emoji() {
+            Some(entry) if entry.emoji.skin_tones => {
+                self.state.emoji = EmojiEntry::skin_tones(entry.emoji);
+                self.state.selected = Selection::History(0);
+                self.snap()
+            }
+            _ => Command::none(),
+        }
+    }
+
+    fn run_script(&mut self, name: &str) -> Command<Message> {
+        let mode = match THEME.script_mode(name) {
+            Some(mode) => mode,
+            None => return Command::none(),
+        };
+
+        self.state.script_seq += 1;
+        let seq = self.state.script_seq;
+        let query = self.state.input_value.pop_query.clone();
+        Command::perform(mode.search(query), move |entries| {
+            Message::ScriptEntries(seq, entries)
+        })
+    }
+
+    fn selected_script_entry(&self) -> Option<&ScriptEntry> {
+        self.state.script_entries.get(self.selected()?)
+    }
+
+    // The typed query comes first, then past searches and the engine suggestions once fetched
+    fn search_engine(&mut self, name: &str) -> Command<Message> {
+        if THEME.web_engine(name).is_none() {
+            return Command::none();
+        }
+
+        let query = self.state.input_value.pop_query.trim().to_string();
+        let mut rows = vec![];
+        if !query.is_empty() {
+            rows.push(EngineQuery {
+                query: query.clone(),
+                source: QuerySource::Input,
+            });
+        }
+
+        let lowercase = query.to_lowercase();
+        rows.extend(
+            self.state
+                .cache
+                .web_history(name)
+                .iter()
+                .filter(|entry| entry.query != query)
+                .filter(|entry| entry.query.to_lowercase().starts_with(&lowercase))
+                .map(|entry| EngineQuery {
+                    query: entry.query.to_string(),
+                    source: QuerySource::History,
+                }),
+        );
+
+        self.state.engine_queries = rows;
+        self.state.engine_seq += 1;
+        let seq = self.state.engine_seq;
+        match self.search_debounce() {
+            Some(delay) => Command::perform(tokio::time::sleep(delay), move |()| {
+                Message::EngineDebounced(seq)
+            }),
+            None => self.engine_suggestions(name, seq),
+        }
+    }
+
+    fn engine_suggestions(&self, name: &str, seq: u64) -> Command<Message> {
+        match THEME.web_engine(name) {
+            Some(engine) => {
+                let query = self.state.input_value.pop_query.trim().to_string();
+                Command::perform(engine.suggestions(query), move |suggestions| {
+                    Message::EngineSuggestions(seq, suggestions)
+                })
+            }
+            None => Command::none(),
+        }
+    }
+
+    fn selected_engine_query(&self) -> Option<&EngineQuery> {
+        self.state.engine_queries.get(self.selected()?)
+    }
+
+    // Expensive plugins and web engine suggestions are only queried once the user stopped typing
+    fn search_debounce(&self) -> Option<Duration> {
+        match self.state.get_active_mode() {
+            ActiveMode::Plugin { plugin_name, .. }
+                if THEME.search_debounce > 0 && THEME.debounced_plugins.contains(plugin_name) =>
+            {
+                Some(Duration::from_millis(THEME.search_debounce as u64))
+            }
+            ActiveMode::WebEngine { .. } if THEME.search_debounce > 0 => {
+                Some(Duration::from_millis(THEME.search_debounce as u64))
+            }
+            _ => None,
+        }
+    }
+
+    // The query is sent once pop-launcher answered the search in flight
+    fn send_search(&mut self, query: String) -> Command<Message> {
+        if self.state.search.on_search() {
+            self.pop_request(Request::Search(query))
+                .expect("Unable to send search request to pop-launcher");
+        }
+        Command::none()
+    }
+
+    fn run_command<P: AsRef<Path>>(
+        &self,
+        desktop_entry_path: P,
+        gpu_preference: Option<GpuPreference>,
+    ) -> Command<Message> {
+        let desktop_entry = DesktopEntry::from_path(&desktop_entry_path).unwrap();
+
+        DesktopEntryEntity::persist(
+            &desktop_entry,
+            desktop_entry_path.as_ref(),
+            &self.state.cache.db,
+        );
+
+        let argv = shell_words::split(&desktop_entry.exec);
+        let args = argv.unwrap();
+        let args = args
+            .iter()
             // Filter out special freedesktop syntax
             .filter(|entry| !entry.starts_with('%'))
             .collect::<Vec<&String>>();
 
-        std::process::Command::new(args[0])
-            .args(&args[1..])
-            .spawn()
-            .expect("Command failure");
+        // pop-launcher already resolved the GPU preference from `PrefersNonDefaultGPU`,
+        // entries launched from the history need to read it from the desktop entry
+        let discrete_gpu = match gpu_preference {
+            Some(preference) => !matches!(preference, GpuPreference::Default),
+            None => desktop_entry.prefers_non_default_gpu(),
+        };
+
+        let mut command = std::process::Command::new(args[0]);
+        command.args(&args[1..]);
+
+        if discrete_gpu {
+            debug!("Launching {:?} on the discrete GPU", desktop_entry.name);
+            command.envs(DISCRETE_GPU_ENV);
+        }
 
-        exit(0);
+        command.spawn().expect("Command failure");
+
+        exit(0)
     }
 
-    fn handle_input(&mut self, key_code: KeyCode) -> Command<Message> {
+    fn handle_input(&mut self, key_code: KeyCode, modifiers: Modifiers) -> Command<Message> {
+        if modifiers.alt() {
+            if let Some(shortcut) = Self::shortcut_from_key(key_code) {
+                return self.quick_select(shortcut);
+            }
+        }
+
+        if self.state.context_menu.is_some() {
+            return self.handle_context_menu_input(key_code);
+        }
+
         match key_code {
             KeyCode::Up => {
                 trace!("Selected line : {:?}", self.selected());
-                return self.dec_selected();
+                return self.dec_selected(self.state.viewport.columns);
             }
             KeyCode::Down => {
                 trace!("Selected line : {:?}", self.selected());
-                return self.inc_selected();
+                return self.inc_selected(self.state.viewport.columns);
+            }
+            // Left and right move the text cursor unless results are displayed as a grid
+            KeyCode::Left if self.state.viewport.columns > 1 => return self.dec_selected(1),
+            KeyCode::Right if self.state.viewport.columns > 1 => return self.inc_selected(1),
+            KeyCode::PageUp => return self.page_up(),
+            KeyCode::PageDown => return self.page_down(),
+            // Home and End move the text cursor while there is some input
+            KeyCode::Home if self.navigates_with_home_end(modifiers) => return self.select_first(),
+            KeyCode::End if self.navigates_with_home_end(modifiers) => return self.select_last(),
+            KeyCode::Enter if modifiers.shift() => {
+                if self.state.get_active_mode() == &ActiveMode::Emoji {
+                    return self.show_skin_tones();
+                }
+                self.request_context()
             }
+            KeyCode::Enter | KeyCode::C if modifiers.control() => return self.copy_selected(),
             KeyCode::Enter => return self.on_execute(),
-            KeyCode::Tab => {
+            KeyCode::Tab if !self.state.get_active_mode().is_builtin() => {
                 if let Some(selected) = self.selected() {
                     self.pop_request(Request::Complete(selected as u32))
                         .expect("Unable to send request to pop-launcher");
                 }
             }
+            KeyCode::Escape => return exit(0),
+            _ => {}
+        };
+
+        Command::none()
+    }
+
+    fn handle_context_menu_input(&mut self, key_code: KeyCode) -> Command<Message> {
+        let context_menu = match self.state.context_menu.as_mut() {
+            Some(context_menu) => context_menu,
+            None => return Command::none(),
+        };
+
+        match key_code {
+            KeyCode::Up => context_menu.selected = context_menu.selected.saturating_sub(1),
+            KeyCode::Down => {
+                if context_menu.selected + 1 < context_menu.options.len() {
+                    context_menu.selected += 1;
+                }
+            }
+            KeyCode::Enter => self.activate_context_option(),
+            // Close the submenu and go back to the search results
             KeyCode::Escape => {
-                exit(0);
+                self.state.context_menu = None;
+                return self.snap();
             }
             _ => {}
         };
@@ -414,15 +1476,222 @@ impl Onagre<'_> {
         Command::none()
     }
 
+    fn activate_context_option(&mut self) {
+        if let Some(context_menu) = self.state.context_menu.take() {
+            if let Some(option) = context_menu.options.get(context_menu.selected) {
+                self.pop_request(Request::ActivateContext {
+                    id: context_menu.id,
+                    context: option.id,
+                })
+                .expect("Unable to send context activation request to pop-launcher");
+            }
+        }
+    }
+
+    fn shortcut_from_key(key_code: KeyCode) -> Option<usize> {
+        match key_code {
+            KeyCode::Key1 | KeyCode::Numpad1 => Some(1),
+            KeyCode::Key2 | KeyCode::Numpad2 => Some(2),
+            KeyCode::Key3 | KeyCode::Numpad3 => Some(3),
+            KeyCode::Key4 | KeyCode::Numpad4 => Some(4),
+            KeyCode::Key5 | KeyCode::Numpad5 => Some(5),
+            KeyCode::Key6 | KeyCode::Numpad6 => Some(6),
+            KeyCode::Key7 | KeyCode::Numpad7 => Some(7),
+            KeyCode::Key8 | KeyCode::Numpad8 => Some(8),
+            KeyCode::Key9 | KeyCode::Numpad9 => Some(9),
+            _ => None,
+        }
+    }
+
+    // Activate the row displaying the `shortcut` badge
+    fn quick_select(&mut self, shortcut: usize) -> Command<Message> {
+        if let Some(context_menu) = self.state.context_menu.as_mut() {
+            if let Some(idx) = shortcut_entry(0..context_menu.options.len(), shortcut) {
+                context_menu.selected = idx;
+                self.activate_context_option();
+            }
+
+            return Command::none();
+        }
+
+        let sections = &self.state.sections;
+        let lines = sections.lines(self.current_entries_len());
+        let visible = sections.entries(self.state.viewport.range(lines));
+        let idx = match shortcut_entry(visible, shortcut) {
+            Some(idx) => idx,
+            None => return Command::none(),
+        };
+
+        self.select(idx);
+        self.on_execute()
+    }
+
+    // Ask pop-launcher for the context options of the selected search result,
+    // history entries don't have any.
+    fn request_context(&mut self) {
+        if let Selection::PopLauncher(selected) = self.state.selected {
+            let entry = self
+                .pop_index(selected)
+                .and_then(|idx| self.state.pop_search.get(idx));
+            if let Some(entry) = entry {
+                self.pop_request(Request::Context(entry.id))
+                    .expect("Unable to send context request to pop-launcher");
+            }
+        }
+    }
+
+    // Move the viewport so the selected row is fully visible
     fn snap(&mut self) -> Command<Message> {
-        let total_items = self.current_entries_len() as f32;
+        let offset = self.state.viewport.offset;
         match self.selected() {
-            None => scrollable::snap_to(SCROLL_ID.clone(), RelativeOffset::START),
+            None => self.state.viewport.reset(),
             Some(selected) => {
-                let offset = (1.0 / total_items) * selected as f32;
-                scrollable::snap_to(SCROLL_ID.clone(), RelativeOffset { x: 0.0, y: offset })
+                let line = self.state.sections.line_of(selected);
+                self.state.viewport.follow(line)
+            }
+        }
+
+        let selected = self.selected();
+        self.state.animations.select(selected);
+        self.state
+            .animations
+            .scroll(offset, self.state.viewport.offset);
+
+        Command::batch(vec![self.scroll_step(), self.update_preview()])
+    }
+
+    // While scrolling, rows between the previous and the current offset are displayed
+    // and the scrollable moves through them
+    fn scroll_step(&self) -> Command<Message> {
+        let (from, progress) = match self.state.animations.scroll_position() {
+            Some(scroll) => scroll,
+            None => return Command::none(),
+        };
+
+        let to = self.state.viewport.offset;
+        // The viewport moved back to where the scroll started, there is nothing to animate
+        if from == to {
+            return Command::none();
+        }
+
+        let distance = from.abs_diff(to) as f32;
+        let position = from as f32 + (to as f32 - from as f32) * progress;
+        let y = (position - from.min(to) as f32) / distance;
+
+        scrollable::snap_to(SCROLL_ID.clone(), RelativeOffset { x: 0.0, y })
+    }
+
+    fn highlight(&self) -> Highlight {
+        match self.state.animations.selection() {
+            Some((previous, progress)) => Highlight {
+                selected: self.selected(),
+                previous,
+                progress,
+            },
+            None => Highlight::from(self.selected()),
+        }
+    }
+
+    // Build the preview of the selected search result, file content is loaded asynchronously
+    fn update_preview(&mut self) -> Command<Message> {
+        if THEME.preview().is_none() {
+            return Command::none();
+        }
+
+        let next = match self.state.selected {
+            Selection::PopLauncher(selected) => match self.pop_index(selected) {
+                Some(idx) => self
+                    .state
+                    .pop_search
+                    .get(idx)
+                    .map(Preview::from_search_result),
+                None => self.state.calc.as_ref().map(Preview::from_calc),
+            },
+            Selection::History(_) if self.state.get_active_mode() == &ActiveMode::Calc => {
+                self.state.calc.as_ref().map(Preview::from_calc)
+            }
+            _ => None,
+        };
+
+        if let (Some(current), Some(next)) = (&self.state.preview, &next) {
+            if current.name == next.name
+                && current.description == next.description
+                && current.paths == next.paths
+            {
+                return Command::none();
             }
         }
+
+        let paths = next
+            .as_ref()
+            .map(|preview| preview.paths.clone())
+            .unwrap_or_default();
+        self.state.preview = next;
+
+        if paths.is_empty() {
+            return Command::none();
+        }
+
+        Command::perform(preview::load(paths.clone()), move |content| {
+            Message::PreviewLoaded(paths, content)
+        })
+    }
+
+    fn select(&mut self, idx: usize) {
+        self.state.selected = match self.state.get_active_mode() {
+            ActiveMode::History | ActiveMode::Web { .. } => Selection::History(idx),
+            mode if mode.is_builtin() => Selection::History(idx),
+            ActiveMode::Plugin { history, .. } if *history => Selection::History(idx),
+            _ => Selection::PopLauncher(idx),
+        };
+    }
+
+    fn page_up(&mut self) -> Command<Message> {
+        if let Some(selected) = self.selected() {
+            self.select(selected.saturating_sub(self.state.viewport.page()));
+        }
+
+        self.snap()
+    }
+
+    fn page_down(&mut self) -> Command<Message> {
+        let total_items = self.current_entries_len();
+        if total_items == 0 {
+            return Command::none();
+        }
+
+        let page = self.state.viewport.page();
+        let selected = match self.selected() {
+            Some(selected) => selected + page,
+            None => page - 1,
+        };
+
+        self.select(selected.min(total_items - 1));
+        self.snap()
+    }
+
+    fn select_first(&mut self) -> Command<Message> {
+        if self.current_entries_len() > 0 {
+            self.select(0);
+        }
+
+        self.snap()
+    }
+
+    fn select_last(&mut self) -> Command<Message> {
+        let total_items = self.current_entries_len();
+        if total_items > 0 {
+            self.select(total_items - 1);
+        }
+
+        self.snap()
+    }
+
+    fn navigates_with_home_end(&self, modifiers: Modifiers) -> bool {
+        self.state.input_value.input_display.is_empty()
+            || modifiers.control()
+            || modifiers.alt()
+            || modifiers.logo()
     }
 
     fn on_pop_launcher_message(&mut self, message: SubscriptionMessage) -> Command<Message> {
@@ -431,20 +1700,39 @@ impl Onagre<'_> {
                 self.request_tx = Some(sender);
             }
             SubscriptionMessage::PopMessage(response) => match response {
-                Response::Close => exit(0),
-                Response::Context { .. } => todo!("Discrete graphics is not implemented"),
-                Response::DesktopEntry { path, .. } => {
+                Response::Close => return exit(0),
+                Response::Context { id, options } => {
+                    if options.is_empty() {
+                        debug!("No context options for entry {id}");
+                    } else {
+                        self.state.context_menu = Some(ContextMenu {
+                            id,
+                            options,
+                            selected: 0,
+                        });
+
+                        return scrollable::snap_to(SCROLL_ID.clone(), RelativeOffset::START);
+                    }
+                }
+                Response::DesktopEntry {
+                    path,
+                    gpu_preference,
+                    ..
+                } => {
                     debug!("Launch DesktopEntry {path:?} via run_command");
-                    let _ = self.run_command(path);
+                    return self.run_command(path, Some(gpu_preference));
                 }
                 Response::Update(search_updates) => {
-                    if self.state.exec_on_next_search {
-                        debug!("Launch entry 0 via PopRequest::Activate");
-                        self.pop_request(Request::Activate(0))
-                            .expect("Unable to send Activate request to pop-launcher");
-                        return Command::none();
+                    // A newer query was typed while pop-launcher was busy with this one
+                    match self.state.search.on_response(search_updates) {
+                        Some(search_updates) => return self.on_search_update(search_updates),
+                        None => debug!("Discarding stale search response"),
+                    }
+
+                    if self.state.search.take_queued() {
+                        let query = self.state.get_input();
+                        return self.send_search(query);
                     }
-                    self.state.pop_search = search_updates;
                 }
                 Response::Fill(fill) => self.complete(fill),
             },
@@ -453,6 +1741,18 @@ impl Onagre<'_> {
         Command::none()
     }
 
+    fn on_search_update(&mut self, search_updates: Vec<SearchResult>) -> Command<Message> {
+        if self.state.exec_on_next_search {
+            debug!("Launch entry 0 via PopRequest::Activate");
+            self.pop_request(Request::Activate(0))
+                .expect("Unable to send Activate request to pop-launcher");
+            return Command::none();
+        }
+        self.state.pop_search = search_updates;
+        self.update_sections();
+        self.update_preview()
+    }
+
     fn complete(&mut self, fill: String) {
         let filled = if THEME.plugin_hint().is_none() {
             self.state.input_value.input_display = fill;
@@ -472,6 +1772,15 @@ impl Onagre<'_> {
     }
 
     fn on_execute(&mut self) -> Command<Message> {
+        if self.is_empty_state() {
+            if let Some((_, search)) = self.web_suggestion() {
+                return Command::batch(vec![
+                    self.on_input_changed(search),
+                    text_input::move_cursor_to_end(INPUT_ID.clone()),
+                ]);
+            }
+        }
+
         match &self.state.get_active_mode() {
             ActiveMode::Plugin {
                 plugin_name,
@@ -493,8 +1802,7 @@ impl Onagre<'_> {
                     self.state.exec_on_next_search = true;
                     let command = self.current_entry().unwrap();
                     self.state.set_input(&command);
-                    self.pop_request(Request::Search(command))
-                        .expect("Unable to send pop-launcher request");
+                    return self.send_search(command);
                 }
             }
             ActiveMode::Web { modifier, .. } => {
@@ -510,33 +1818,124 @@ impl Onagre<'_> {
                     let command = self.current_entry().unwrap();
                     self.state.set_input(&command);
                     self.state.exec_on_next_search = true;
-                    self.pop_request(Request::Search(command))
-                        .expect("Unable to send pop-launcher request")
+                    return self.send_search(command);
                 }
             }
             ActiveMode::History => {
                 let path = self.current_entry();
                 let path = path.unwrap();
-                let _ = self.run_command(path);
+                return self.run_command(path, None);
             }
-            _ => {
-                if self.selected().is_none() {
-                    self.pop_request(Request::Activate(0))
-                        .expect("Unable to send pop-launcher request")
-                } else {
-                    let selected = self.selected().unwrap() as u32;
-                    debug!("Activating pop entry at index {selected}");
-                    self.pop_request(Request::Activate(selected))
-                        .expect("Unable to send pop-launcher request")
+            // Enter copies the result of the calculation
+            ActiveMode::Calc => {
+                if let Some(Ok(result)) = self.state.calc.as_ref().map(|entry| &entry.result) {
+                    return Self::copy_and_exit(result.clone());
+                }
+            }
+            ActiveMode::Windows => {
+                if let (Some(window), Some(window_manager)) =
+                    (self.selected_window(), WINDOW_MANAGER.as_ref())
+                {
+                    match window_manager.focus(window) {
+                        Ok(()) => return exit(0),
+                        Err(err) => error!("Failed to focus window {}: {err}", window.title),
+                    }
+                }
+            }
+            ActiveMode::Ssh => {
+                if let Some(host) = self.selected_ssh_host() {
+                    PluginCommandEntity::persist(ssh::COLLECTION, &host.name, &self.state.cache.db);
+                    let mut command = THEME.terminal();
+                    command.extend(host.command());
+
+                    match std::process::Command::new(&command[0])
+                        .args(&command[1..])
+                        .spawn()
+                    {
+                        Ok(_) => return exit(0),
+                        Err(err) => error!("Failed to run {command:?}: {err}"),
+                    }
+                }
+            }
+            ActiveMode::Emoji => {
+                if let Some(entry) = self.selected_emoji() {
+                    // History is keyed by the character without skin tone
+                    PluginCommandEntity::persist(
+                        emoji::COLLECTION,
+                        entry.emoji.glyph,
+                        &self.state.cache.db,
+                    );
+
+                    match THEME.emoji_action {
+                        EmojiAction::Copy => return Self::copy_and_exit(entry.glyph.clone()),
+                        EmojiAction::Type => match clipboard::type_text(&entry.glyph) {
+                            Ok(()) => return exit(0),
+                            Err(err) => error!("Failed to type {}: {err}", entry.glyph),
+                        },
+                    }
+                }
+            }
+            ActiveMode::Bookmarks => {
+                if let Some(bookmark) = self.selected_bookmark() {
+                    match std::process::Command::new("xdg-open")
+                        .arg(&bookmark.url)
+                        .spawn()
+                    {
+                        Ok(_) => return exit(0),
+                        Err(err) => error!("Failed to open {}: {err}", bookmark.url),
+                    }
+                }
+            }
+            ActiveMode::WebEngine { name, .. } => {
+                if let (Some(engine), Some(entry)) =
+                    (THEME.web_engine(name), self.selected_engine_query())
+                {
+                    WebEntity::persist(&entry.query, name, &self.state.cache.db);
+                    let url = engine.search_url(&entry.query);
+                    match std::process::Command::new("xdg-open").arg(&url).spawn() {
+                        Ok(_) => return exit(0),
+                        Err(err) => error!("Failed to open {url}: {err}"),
+                    }
+                }
+            }
+            ActiveMode::Script { name, .. } => {
+                if let (Some(mode), Some(entry)) =
+                    (THEME.script_mode(name), self.selected_script_entry())
+                {
+                    return Command::perform(
+                        mode.activate(entry.clone()),
+                        Message::ScriptActivated,
+                    );
                 }
             }
+            _ => match self.selected() {
+                None => self
+                    .pop_request(Request::Activate(0))
+                    .expect("Unable to send pop-launcher request"),
+                Some(selected) => match self.pop_index(selected) {
+                    Some(selected) => {
+                        debug!("Activating pop entry at index {selected}");
+                        self.pop_request(Request::Activate(selected as u32))
+                            .expect("Unable to send pop-launcher request")
+                    }
+                    // The calculator row of the desktop entries
+                    None => {
+                        if let Some(Ok(result)) =
+                            self.state.calc.as_ref().map(|entry| &entry.result)
+                        {
+                            return Self::copy_and_exit(result.clone());
+                        }
+                    }
+                },
+            },
         }
 
         Command::none()
     }
 
     fn current_entries_len(&self) -> usize {
-        match &self.state.get_active_mode() {
+        let mode = self.state.get_active_mode();
+        let len = match mode {
             ActiveMode::Plugin {
                 plugin_name,
                 history,
@@ -549,9 +1948,18 @@ impl Onagre<'_> {
                 }
             }
             ActiveMode::History => self.state.cache.de_len(),
-            ActiveMode::DesktopEntry => self.state.pop_search.len(),
+            ActiveMode::DesktopEntry => self.inline_calc_len() + self.state.pop_search.len(),
             ActiveMode::Web { modifier, .. } => self.state.cache.web_history_len(modifier),
-        }
+            ActiveMode::Calc => self.state.calc.iter().len(),
+            ActiveMode::Windows => self.state.windows.len(),
+            ActiveMode::Ssh => self.state.ssh_hosts.len(),
+            ActiveMode::Emoji => self.state.emoji.len(),
+            ActiveMode::Bookmarks => self.state.bookmarks.len(),
+            ActiveMode::Script { .. } => self.state.script_entries.len(),
+            ActiveMode::WebEngine { .. } => self.state.engine_queries.len(),
+        };
+
+        len.min(THEME.max_results(mode.name()))
     }
 
     fn pop_request(&self, request: Request) -> Result<(), TrySendError<Request>> {
@@ -568,17 +1976,34 @@ impl Onagre<'_> {
         }
     }
 
-    fn dec_selected(&mut self) -> Command<Message> {
+    // Number of calculator rows displayed before the pop-launcher results
+    fn inline_calc_len(&self) -> usize {
+        match self.state.get_active_mode() {
+            ActiveMode::DesktopEntry => self.state.calc.iter().len(),
+            _ => 0,
+        }
+    }
+
+    // Index of the pop-launcher result displayed at `idx`, `None` for the calculator row
+    fn pop_index(&self, idx: usize) -> Option<usize> {
+        self.state
+            .sections
+            .entry(idx)
+            .checked_sub(self.inline_calc_len())
+    }
+
+    // Move the selection `step` entries back, a whole row with the grid layout
+    fn dec_selected(&mut self, step: usize) -> Command<Message> {
         match self.state.selected {
             Selection::Reset => self.state.selected = Selection::Reset,
             Selection::History(selected) => {
-                if selected > 0 {
-                    self.state.selected = Selection::History(selected - 1)
+                if selected >= step {
+                    self.state.selected = Selection::History(selected - step)
                 }
             }
             Selection::PopLauncher(selected) => {
-                if selected > 0 {
-                    self.state.selected = Selection::PopLauncher(selected - 1)
+                if selected >= step {
+                    self.state.selected = Selection::PopLauncher(selected - step)
                 }
             }
         };
@@ -586,19 +2011,22 @@ impl Onagre<'_> {
         self.snap()
     }
 
-    fn inc_selected(&mut self) -> Command<Message> {
+    // Move the selection `step` entries forward, a whole row with the grid layout
+    fn inc_selected(&mut self, step: usize) -> Command<Message> {
+        let total_items = self.current_entries_len();
+        let viewport = &self.state.viewport;
         match self.state.selected {
             Selection::Reset => self.state.selected = Selection::History(0),
             Selection::History(selected) => {
-                let total_items = self.current_entries_len();
-                if total_items != 0 && selected < total_items - 1 {
-                    self.state.selected = Selection::History(selected + 1);
+                if total_items != 0 {
+                    self.state.selected =
+                        Selection::History(viewport.advance(selected, step, total_items));
                 }
             }
             Selection::PopLauncher(selected) => {
-                let total_items = self.current_entries_len();
-                if total_items != 0 && selected < total_items - 1 {
-                    self.state.selected = Selection::PopLauncher(selected + 1);
+                if total_items != 0 {
+                    self.state.selected =
+                        Selection::PopLauncher(viewport.advance(selected, step, total_items));
                 }
             }
         };
@@ -608,11 +2036,12 @@ impl Onagre<'_> {
 
     fn keyboard_event() -> Subscription<Message> {
         iced_native::subscription::events_with(|event, _status| match event {
+            Event::Window(window::Event::Focused) => Some(Message::Focused),
             Event::Window(window::Event::Unfocused) => Some(Message::Unfocused),
             Event::Keyboard(iced::keyboard::Event::KeyPressed {
-                modifiers: _,
+                modifiers,
                 key_code,
-            }) => Some(Message::KeyboardEvent(key_code)),
+            }) => Some(Message::KeyboardEvent(key_code, modifiers)),
             _ => None,
         })
     }
@@ -1,9 +1,23 @@
 use crate::app::plugin_matchers::QueryData;
+use crate::windows::WindowManager;
+use crate::THEME;
 use once_cell::sync::Lazy;
 use pop_launcher_toolkit::plugins::web::Config as WebConfig;
 
 pub(crate) static WEB_CONFIG: Lazy<WebConfig> = Lazy::new(pop_launcher_toolkit::plugins::web::load);
 
+pub(crate) static WINDOW_MANAGER: Lazy<Option<WindowManager>> = Lazy::new(WindowManager::detect);
+
+// Prefixes switching to the built-in modes, checked before pop-launcher plugins.
+// `=` selects the built-in calculator instead of pop-launcher's calc plugin.
+const BUILTIN_MODES: [(&str, ActiveMode); 5] = [
+    ("=", ActiveMode::Calc),
+    ("win ", ActiveMode::Windows),
+    ("ssh ", ActiveMode::Ssh),
+    (":", ActiveMode::Emoji),
+    ("bm ", ActiveMode::Bookmarks),
+];
+
 #[derive(Debug, PartialEq, Clone, Default)]
 pub enum ActiveMode {
     #[default]
@@ -17,6 +31,83 @@ pub enum ActiveMode {
         modifier: String,
         history: bool,
     },
+    // Built-in calculator, evaluated without pop-launcher
+    Calc,
+    // Built-in window switcher
+    Windows,
+    // Hosts from the ssh configuration, opened in a terminal
+    Ssh,
+    // Emoji and Unicode symbols picker
+    Emoji,
+    // Firefox and Chromium bookmarks
+    Bookmarks,
+    // Custom mode declared in the theme, entries come from a script
+    Script {
+        name: String,
+        modifier: String,
+    },
+    // Search engine declared in the theme, with optional live suggestions
+    WebEngine {
+        name: String,
+        modifier: String,
+    },
+}
+
+impl ActiveMode {
+    // The name used to refer to this mode in the theme configuration
+    pub fn name(&self) -> &str {
+        match self {
+            ActiveMode::History => "history",
+            ActiveMode::DesktopEntry => "desktop_entries",
+            ActiveMode::Web { .. } => "web",
+            ActiveMode::Plugin { plugin_name, .. } => plugin_name,
+            ActiveMode::Calc => "calc",
+            ActiveMode::Windows => "windows",
+            ActiveMode::Ssh => "ssh",
+            ActiveMode::Emoji => "emoji",
+            ActiveMode::Bookmarks => "bookmarks",
+            ActiveMode::Script { name, .. } => name,
+            ActiveMode::WebEngine { name, .. } => name,
+        }
+    }
+
+    // Built-in modes don't query pop-launcher
+    pub fn is_builtin(&self) -> bool {
+        matches!(
+            self,
+            ActiveMode::Calc
+                | ActiveMode::Windows
+                | ActiveMode::Ssh
+                | ActiveMode::Emoji
+                | ActiveMode::Bookmarks
+                | ActiveMode::Script { .. }
+                | ActiveMode::WebEngine { .. }
+        )
+    }
+
+    // The built-in mode selected by the input prefix, with its prefix and the query following it
+    pub fn from_builtin_prefix(input: &str) -> Option<(&'static str, ActiveMode, &str)> {
+        BUILTIN_MODES
+            .iter()
+            .filter(|(_, mode)| mode.is_available())
+            .find_map(|(prefix, mode)| {
+                input
+                    .strip_prefix(prefix)
+                    .map(|query| (*prefix, mode.clone(), query))
+            })
+    }
+
+    pub fn builtin_prefix(&self) -> Option<&'static str> {
+        BUILTIN_MODES
+            .iter()
+            .find(|(_, mode)| mode == self)
+            .map(|(prefix, _)| *prefix)
+    }
+
+    // Windows can only be listed when the window manager is known
+    fn is_available(&self) -> bool {
+        *self != ActiveMode::Windows || WINDOW_MANAGER.is_some()
+    }
 }
 
 impl From<QueryData> for ActiveMode {
@@ -26,6 +117,15 @@ impl From<QueryData> for ActiveMode {
             "web" => ActiveMode::Web {
                 modifier: query_data.modifier,
             },
+            // Script modes are registered next to pop-launcher plugins to share their matchers
+            name if THEME.script_mode(name).is_some() => ActiveMode::Script {
+                name: query_data.plugin_name,
+                modifier: query_data.modifier,
+            },
+            name if THEME.web_engine(name).is_some() => ActiveMode::WebEngine {
+                name: query_data.plugin_name,
+                modifier: query_data.modifier,
+            },
             _other => ActiveMode::Plugin {
                 plugin_name: query_data.plugin_name,
                 modifier: query_data.modifier,
@@ -34,3 +134,26 @@ impl From<QueryData> for ActiveMode {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::app::mode::ActiveMode;
+    use speculoos::prelude::*;
+
+    #[test]
+    fn should_select_builtin_modes_from_their_prefix() {
+        assert_that!(ActiveMode::from_builtin_prefix("=2+2")).is_equal_to(Some((
+            "=",
+            ActiveMode::Calc,
+            "2+2",
+        )));
+        assert_that!(ActiveMode::from_builtin_prefix("bm rust")).is_equal_to(Some((
+            "bm ",
+            ActiveMode::Bookmarks,
+            "rust",
+        )));
+        assert_that!(ActiveMode::from_builtin_prefix("sshd")).is_none();
+        assert_that!(ActiveMode::Emoji.builtin_prefix()).is_equal_to(Some(":"));
+        assert_that!(ActiveMode::DesktopEntry.builtin_prefix()).is_none();
+    }
+}
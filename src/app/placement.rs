@@ -0,0 +1,259 @@
+use crate::app::mode::WINDOW_MANAGER;
+use crate::windows::{Output, WindowManager};
+use crate::THEME;
+use log::error;
+use once_cell::sync::Lazy;
+
+// Winit can't read the monitor layout before the window is created,
+// it is queried from the window manager instead. This blocks the startup,
+// it only happens when the theme places the window or on sway to read the scale.
+static OUTPUTS: Lazy<Vec<Output>> = Lazy::new(|| {
+    WINDOW_MANAGER
+        .as_ref()
+        .map(|window_manager| {
+            window_manager
+                .outputs()
+                .map_err(|err| error!("Failed to list outputs: {err}"))
+                .unwrap_or_default()
+        })
+        .unwrap_or_default()
+});
+
+// Without placement options winit centers the window
+pub static POSITION: Lazy<Option<(i32, i32)>> = Lazy::new(|| {
+    let window_manager = WINDOW_MANAGER.as_ref()?;
+    if THEME.placement.is_default() {
+        return None;
+    }
+
+    THEME.placement.position(
+        &OUTPUTS,
+        window_manager.cursor(),
+        super::scaled_size(THEME.size),
+    )
+});
+
+// Scale factor of the monitor displaying onagre. Only sway reports it, over its IPC socket.
+pub fn output_scale() -> Option<f32> {
+    match WINDOW_MANAGER.as_ref()? {
+        window_manager @ WindowManager::Ipc(_) => {
+            THEME.placement.scale(&OUTPUTS, window_manager.cursor())
+        }
+        WindowManager::Ewmh => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    Top,
+    Center,
+    Cursor,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Offset {
+    Pixels(i32),
+    // Percentage of the monitor size
+    Percent(f32),
+}
+
+impl Offset {
+    fn resolve(&self, extent: u32) -> i32 {
+        match self {
+            Offset::Pixels(pixels) => *pixels,
+            Offset::Percent(percent) => (extent as f32 * percent / 100.0).round() as i32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Monitor {
+    Focused,
+    Primary,
+    Named(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placement {
+    pub anchor: Anchor,
+    pub offset_x: Offset,
+    pub offset_y: Offset,
+    pub monitor: Monitor,
+    // Ask sway and i3 to float the window instead of tiling it
+    pub floating: bool,
+}
+
+impl Default for Placement {
+    fn default() -> Self {
+        Placement {
+            anchor: Anchor::Center,
+            offset_x: Offset::Pixels(0),
+            offset_y: Offset::Pixels(0),
+            monitor: Monitor::Focused,
+            floating: false,
+        }
+    }
+}
+
+impl Placement {
+    pub fn is_default(&self) -> bool {
+        *self == Placement::default()
+    }
+
+    // Top left corner of a window of the given size, the window never overflows its monitor
+    pub fn position(
+        &self,
+        outputs: &[Output],
+        cursor: Option<(i32, i32)>,
+        (width, height): (u32, u32),
+    ) -> Option<(i32, i32)> {
+        let cursor = cursor.filter(|_| self.anchor == Anchor::Cursor);
+        let output = self.output(outputs, cursor)?;
+        let (width, height) = (width as i32, height as i32);
+        let centered_x = output.x + (output.width as i32 - width) / 2;
+        let centered_y = output.y + (output.height as i32 - height) / 2;
+
+        let (x, y) = match (self.anchor, cursor) {
+            (Anchor::Top, _) => (centered_x, output.y),
+            (Anchor::Cursor, Some((x, y))) => (x - width / 2, y),
+            // The pointer position is unknown on Wayland
+            (Anchor::Center, _) | (Anchor::Cursor, None) => (centered_x, centered_y),
+        };
+
+        let x = x + self.offset_x.resolve(output.width);
+        let y = y + self.offset_y.resolve(output.height);
+        let max_x = output.x + (output.width as i32 - width).max(0);
+        let max_y = output.y + (output.height as i32 - height).max(0);
+
+        Some((x.clamp(output.x, max_x), y.clamp(output.y, max_y)))
+    }
+
+    pub fn scale(&self, outputs: &[Output], cursor: Option<(i32, i32)>) -> Option<f32> {
+        let cursor = cursor.filter(|_| self.anchor == Anchor::Cursor);
+        self.output(outputs, cursor)?.scale
+    }
+
+    fn output<'a>(&self, outputs: &'a [Output], cursor: Option<(i32, i32)>) -> Option<&'a Output> {
+        let selected = match (&self.monitor, cursor) {
+            // Follow the pointer whatever the configured monitor
+            (_, Some(cursor)) => outputs.iter().find(|output| output.contains(cursor)),
+            (Monitor::Focused, None) => outputs.iter().find(|output| output.focused),
+            (Monitor::Primary, None) => outputs.iter().find(|output| output.primary),
+            (Monitor::Named(name), None) => outputs.iter().find(|output| output.name == *name),
+        };
+
+        selected
+            .or_else(|| outputs.iter().find(|output| output.focused))
+            .or_else(|| outputs.first())
+    }
+}
+
+// Winit can't position windows on Wayland, the window manager moves it once it is mapped.
+// Called when the window first gets the focus, it is mapped by then.
+pub fn float(title: &str) {
+    let (window_manager, position) = match (WINDOW_MANAGER.as_ref(), *POSITION) {
+        (Some(window_manager), Some(position)) if THEME.placement.floating => {
+            (window_manager, position)
+        }
+        _ => return,
+    };
+
+    if let Err(err) = window_manager.float(title, position) {
+        error!("Failed to float the onagre window titled '{title}': {err}");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::placement::{Anchor, Monitor, Offset, Placement};
+    use crate::windows::Output;
+    use speculoos::prelude::*;
+
+    fn outputs() -> Vec<Output> {
+        vec![
+            Output {
+                name: "eDP-1".to_string(),
+                x: 0,
+                y: 0,
+                width: 1920,
+                height: 1080,
+                focused: false,
+                primary: true,
+                scale: Some(2.0),
+            },
+            Output {
+                name: "HDMI-1".to_string(),
+                x: 1920,
+                y: 0,
+                width: 2560,
+                height: 1440,
+                focused: true,
+                primary: false,
+                scale: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn should_center_on_focused_output() {
+        let placement = Placement::default();
+
+        let position = placement.position(&outputs(), None, (400, 300));
+
+        assert_that!(position).is_equal_to(Some((3000, 570)));
+    }
+
+    #[test]
+    fn should_anchor_to_the_top_of_a_named_output_with_offsets() {
+        let placement = Placement {
+            anchor: Anchor::Top,
+            offset_x: Offset::Pixels(-100),
+            offset_y: Offset::Percent(10.0),
+            monitor: Monitor::Named("eDP-1".to_string()),
+            ..Default::default()
+        };
+
+        let position = placement.position(&outputs(), None, (400, 300));
+
+        assert_that!(position).is_equal_to(Some((660, 108)));
+    }
+
+    #[test]
+    fn should_follow_the_cursor_and_stay_on_screen() {
+        let placement = Placement {
+            anchor: Anchor::Cursor,
+            monitor: Monitor::Primary,
+            ..Default::default()
+        };
+
+        let near_edge = placement.position(&outputs(), Some((4400, 1400)), (400, 300));
+        let unknown = placement.position(&outputs(), None, (400, 300));
+
+        assert_that!(near_edge).is_equal_to(Some((1920 + 2560 - 400, 1440 - 300)));
+        assert_that!(unknown).is_equal_to(Some((760, 390)));
+    }
+
+    #[test]
+    fn should_fall_back_to_focused_output() {
+        let placement = Placement {
+            monitor: Monitor::Named("DP-3".to_string()),
+            ..Default::default()
+        };
+
+        let position = placement.position(&outputs(), None, (400, 300));
+
+        assert_that!(position).is_equal_to(Some((3000, 570)));
+        assert_that!(placement.position(&[], None, (400, 300))).is_none();
+    }
+
+    #[test]
+    fn should_read_the_scale_of_the_selected_output() {
+        let primary = Placement {
+            monitor: Monitor::Primary,
+            ..Default::default()
+        };
+
+        assert_that!(primary.scale(&outputs(), None)).is_equal_to(Some(2.0));
+        assert_that!(Placement::default().scale(&outputs(), None)).is_none();
+    }
+}
@@ -1,14 +1,21 @@
+use crate::app::entries::script_entry::script_icon;
 use crate::app::mode::WEB_CONFIG;
+use crate::engines::WebEngine;
 use crate::icons::IconPath;
+use crate::scripts::ScriptMode;
 use regex::Regex;
 
 #[derive(Debug, Clone)]
 pub struct Plugin {
     pub name: String,
     pub icon: Option<IconPath>,
+    // Icon name of the plugin config, pop-launcher uses it as the category icon of its results
+    pub category: Option<String>,
     pub history: bool,
     pub help: Option<String>,
     pub regex: Option<Regex>,
+    // Theme modes are only entered by typing their prefix, their name can appear in any query
+    pub prefix_only: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -54,6 +61,34 @@ impl Plugin {
     }
 }
 
+impl From<&ScriptMode> for Plugin {
+    fn from(mode: &ScriptMode) -> Self {
+        Plugin {
+            name: mode.name.clone(),
+            icon: mode.icon.as_deref().and_then(script_icon),
+            category: None,
+            history: false,
+            help: Some(mode.prefix.clone()),
+            regex: Regex::new(&format!("^{}", regex::escape(&mode.prefix))).ok(),
+            prefix_only: true,
+        }
+    }
+}
+
+impl From<&WebEngine> for Plugin {
+    fn from(engine: &WebEngine) -> Self {
+        Plugin {
+            name: engine.name.clone(),
+            icon: engine.icon.as_deref().and_then(script_icon),
+            category: None,
+            history: true,
+            help: Some(engine.prefix.clone()),
+            regex: Regex::new(&format!("^{}", regex::escape(&engine.prefix))).ok(),
+            prefix_only: true,
+        }
+    }
+}
+
 pub fn match_web_plugins(text: &str) -> Option<QueryData> {
     text.split_once(' ').and_then(|(mode, query)| {
         if WEB_CONFIG.get(mode).is_some() {
@@ -66,6 +101,10 @@ pub fn match_web_plugins(text: &str) -> Option<QueryData> {
 
 impl Plugin {
     pub fn try_match(&self, text: &str) -> Option<QueryData> {
+        if self.prefix_only {
+            return self.match_plugin_regex(text);
+        }
+
         self.match_plugin_help(text)
             .or_else(|| self.match_plugin_regex(text))
     }
@@ -109,6 +148,8 @@ impl Plugin {
 #[cfg(test)]
 mod test {
     use crate::app::plugin_matchers::{Plugin, QueryData};
+    use crate::engines::WebEngine;
+    use crate::scripts::ScriptMode;
     use regex::Regex;
 
     #[test]
@@ -116,9 +157,11 @@ mod test {
         let plugin = Plugin {
             name: "find".to_string(),
             icon: None,
+            category: None,
             history: false,
             help: Some("find ".to_string()),
             regex: Some(Regex::new("^(find )+").unwrap()),
+            prefix_only: false,
         };
 
         let match_ = plugin.try_match("find some text");
@@ -140,9 +183,11 @@ mod test {
         let plugin = Plugin {
             name: "find".to_string(),
             icon: None,
+            category: None,
             history: false,
             help: Some("find ".to_string()),
             regex: Some(Regex::new("^(find )+").unwrap()),
+            prefix_only: false,
         };
 
         let match_ = plugin.try_match("fin");
@@ -155,9 +200,11 @@ mod test {
         let plugin = Plugin {
             name: "find".to_string(),
             icon: None,
+            category: None,
             history: false,
             help: Some("find ".to_string()),
             regex: Some(Regex::new("^(find )+").unwrap()),
+            prefix_only: false,
         };
 
         let match_ = plugin.try_match("find ");
@@ -173,4 +220,38 @@ mod test {
             })
         );
     }
+
+    #[test]
+    fn should_match_script_mode_by_prefix_only() {
+        let mode = ScriptMode {
+            name: "notes".to_string(),
+            prefix: "n ".to_string(),
+            ..ScriptMode::default()
+        };
+        let plugin = Plugin::from(&mode);
+
+        assert_eq!(plugin.try_match("my notes"), None);
+        assert_eq!(
+            plugin.try_match("n groceries").map(|data| data.query),
+            Some("groceries".to_string())
+        );
+    }
+
+    #[test]
+    fn should_match_web_engine_by_prefix_only() {
+        let engine = WebEngine {
+            name: "jira".to_string(),
+            prefix: "j ".to_string(),
+            icon: None,
+            url: "https://jira.example.com/search?q={query}".to_string(),
+            suggestions: None,
+        };
+        let plugin = Plugin::from(&engine);
+
+        assert_eq!(plugin.try_match("open jira ticket"), None);
+        assert_eq!(
+            plugin.try_match("j ONA-42").map(|data| data.query),
+            Some("ONA-42".to_string())
+        );
+    }
 }
@@ -0,0 +1,233 @@
+use crate::app::entries::calc_entry::CalcEntry;
+use crate::app::entries::AsEntry;
+use crate::app::style::rows::generic::GenericContainerStyle;
+use crate::app::Message;
+use iced::widget::{Column, Container, Image, Svg, Text};
+use iced::Length;
+use pop_launcher_toolkit::launcher::SearchResult;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncReadExt;
+
+// Number of bytes and lines read from a text file to build its preview
+const TEXT_HEAD_BYTES: usize = 4096;
+const TEXT_HEAD_LINES: usize = 30;
+// Number of directory entries listed in the preview
+const DIRECTORY_ENTRIES: usize = 50;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PreviewContent {
+    Text(String),
+    Image(PathBuf),
+    Svg(PathBuf),
+    Directory(Vec<String>),
+}
+
+// The preview of the selected search result, file content is loaded asynchronously
+#[derive(Debug)]
+pub struct Preview {
+    pub name: String,
+    pub description: String,
+    // Paths the result may point to, the first existing one is previewed
+    pub paths: Vec<PathBuf>,
+    pub content: Option<PreviewContent>,
+}
+
+impl Preview {
+    pub fn from_search_result(result: &SearchResult) -> Self {
+        Self {
+            name: result.name.clone(),
+            description: result.description.clone(),
+            paths: paths_of(result),
+            content: None,
+        }
+    }
+
+    // The result of the calculator row and the evaluated expression, or the error
+    pub fn from_calc(calc: &CalcEntry) -> Self {
+        Self {
+            name: calc.get_display_name().to_string(),
+            description: calc.get_description().unwrap_or_default().to_string(),
+            paths: vec![],
+            content: None,
+        }
+    }
+
+    pub fn view(&self, style: &'static GenericContainerStyle) -> Container<'_, Message> {
+        let column = Column::new()
+            .spacing(style.font_size / 2)
+            .push(Text::new(&self.name).size(style.font_size + 4))
+            .push(Text::new(&self.description).size(style.font_size));
+
+        let column = match &self.content {
+            Some(PreviewContent::Text(text)) => column.push(Text::new(text).size(style.font_size)),
+            Some(PreviewContent::Directory(entries)) => {
+                column.push(Text::new(entries.join("\n")).size(style.font_size))
+            }
+            Some(PreviewContent::Image(path)) => column.push(Image::new(path).width(Length::Fill)),
+            Some(PreviewContent::Svg(path)) => {
+                column.push(Svg::from_path(path).width(Length::Fill))
+            }
+            None => column,
+        };
+
+        Container::new(column)
+    }
+}
+
+// File plugins (find, recent, files) put the file path in the search result description,
+// whether it exists is only checked when loading the preview
+pub fn paths_of(result: &SearchResult) -> Vec<PathBuf> {
+    [result.description.as_str(), result.name.as_str()]
+        .iter()
+        .filter_map(|candidate| expand_path(candidate))
+        .collect()
+}
+
+fn expand_path(candidate: &str) -> Option<PathBuf> {
+    let candidate = candidate.trim();
+    let candidate = candidate.strip_prefix("file://").unwrap_or(candidate);
+
+    if let Some(relative) = candidate.strip_prefix("~/") {
+        dirs::home_dir().map(|home| home.join(relative))
+    } else if candidate.starts_with('/') {
+        Some(PathBuf::from(candidate))
+    } else {
+        None
+    }
+}
+
+// Preview of the first of `paths` that exists
+pub async fn load(paths: Vec<PathBuf>) -> Option<PreviewContent> {
+    for path in paths {
+        if let Ok(metadata) = tokio::fs::metadata(&path).await {
+            return load_path(path, metadata.is_dir()).await;
+        }
+    }
+
+    None
+}
+
+async fn load_path(path: PathBuf, is_dir: bool) -> Option<PreviewContent> {
+    if is_dir {
+        return list_directory(&path).await.map(PreviewContent::Directory);
+    }
+
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+
+    match extension.as_deref() {
+        Some("png" | "jpg" | "jpeg" | "gif" | "bmp" | "webp" | "ico") => {
+            Some(PreviewContent::Image(path))
+        }
+        Some("svg") => Some(PreviewContent::Svg(path)),
+        _ => {
+            let mut buffer = Vec::with_capacity(TEXT_HEAD_BYTES);
+            tokio::fs::File::open(&path)
+                .await
+                .ok()?
+                .take(TEXT_HEAD_BYTES as u64)
+                .read_to_end(&mut buffer)
+                .await
+                .ok()?;
+
+            text_head(&buffer).map(PreviewContent::Text)
+        }
+    }
+}
+
+async fn list_directory(path: &Path) -> Option<Vec<String>> {
+    let mut read_dir = tokio::fs::read_dir(path).await.ok()?;
+    let mut entries = vec![];
+
+    while let Ok(Some(entry)) = read_dir.next_entry().await {
+        let mut name = entry.file_name().to_string_lossy().to_string();
+        if entry.file_type().await.map(|t| t.is_dir()).unwrap_or(false) {
+            name.push('/');
+        }
+
+        entries.push(name);
+    }
+
+    entries.sort();
+    entries.truncate(DIRECTORY_ENTRIES);
+    Some(entries)
+}
+
+// Returns the first lines of a text file, or None if the content looks binary
+fn text_head(content: &[u8]) -> Option<String> {
+    if content.contains(&0) {
+        return None;
+    }
+
+    let text = String::from_utf8_lossy(content);
+    let head = text
+        .lines()
+        .take(TEXT_HEAD_LINES)
+        .collect::<Vec<&str>>()
+        .join("\n");
+
+    Some(head)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::preview::{expand_path, load, text_head, PreviewContent};
+    use crate::fixtures::fixture_dir;
+    use speculoos::prelude::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn should_expand_file_uri() {
+        let path = expand_path("file:///etc/hosts");
+
+        assert_that!(path).is_equal_to(Some(PathBuf::from("/etc/hosts")));
+    }
+
+    #[test]
+    fn should_expand_home_directory() {
+        let path = expand_path("~/Documents");
+
+        assert_that!(path).is_equal_to(dirs::home_dir().map(|home| home.join("Documents")));
+    }
+
+    #[test]
+    fn should_not_expand_plain_text() {
+        let path = expand_path("Firefox web browser");
+
+        assert_that!(path).is_none();
+    }
+
+    #[tokio::test]
+    async fn should_preview_the_first_existing_path() {
+        let dir = fixture_dir("preview");
+        fs::write(dir.join("notes.txt"), "first line\nsecond line").unwrap();
+
+        let content = load(vec![dir.join("missing.txt"), dir.join("notes.txt")]).await;
+
+        assert_that!(content).is_equal_to(Some(PreviewContent::Text(
+            "first line\nsecond line".to_string(),
+        )));
+        assert_that!(load(vec![dir.join("missing.txt")]).await).is_none();
+    }
+
+    #[test]
+    fn should_ignore_binary_content() {
+        let head = text_head(&[0x7f, b'E', b'L', b'F', 0, 0]);
+
+        assert_that!(head).is_none();
+    }
+
+    #[test]
+    fn should_keep_first_lines() {
+        let content = (0..100)
+            .map(|line| line.to_string())
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        let head = text_head(content.as_bytes()).unwrap();
+
+        assert_that!(head.lines().count()).is_equal_to(30);
+    }
+}
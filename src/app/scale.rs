@@ -0,0 +1,72 @@
+use crate::app::placement;
+use crate::THEME;
+use once_cell::sync::Lazy;
+
+// Sizes in the theme are logical pixels: iced multiplies them by the window scale factor
+// and by `Application::scale_factor`. Icons are looked up and rasterized at the resulting
+// physical size so they stay sharp on HiDPI displays.
+static DISPLAY_SCALE: Lazy<f32> = Lazy::new(|| {
+    placement::output_scale().unwrap_or_else(|| detect(|key| std::env::var(key).ok()))
+});
+
+// The smallest scale accepted from the theme, lower values make onagre unusable
+const MIN_SCALE: f32 = 0.25;
+
+// The `--scale` theme multiplier
+pub fn ui_scale() -> f32 {
+    THEME.scale.max(MIN_SCALE)
+}
+
+// Winit does not expose the monitor scale before the window is created. Sway reports
+// it with the outputs, elsewhere we read the variables toolkits use to configure it.
+fn detect<F>(var: F) -> f32
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let factor = |key: &str| {
+        var(key)
+            .and_then(|value| value.trim().parse::<f32>().ok())
+            .filter(|value| *value > 0.0)
+    };
+
+    if let Some(scale) = factor("GDK_SCALE") {
+        // `GDK_DPI_SCALE` only scales text but is commonly used for fractional scaling
+        scale * factor("GDK_DPI_SCALE").unwrap_or(1.0)
+    } else {
+        factor("QT_SCALE_FACTOR")
+            .or_else(|| factor("WINIT_X11_SCALE_FACTOR"))
+            .unwrap_or(1.0)
+    }
+}
+
+// Integer scale used to pick `@2x` icon theme directories
+pub fn icon_scale() -> u16 {
+    (ui_scale() * *DISPLAY_SCALE).ceil().max(1.0) as u16
+}
+
+// Size in physical pixels of an icon of `size` logical pixels
+pub fn icon_pixels(size: u16) -> u16 {
+    (f32::from(size) * ui_scale() * *DISPLAY_SCALE).round() as u16
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::scale::detect;
+    use speculoos::prelude::*;
+    use std::collections::HashMap;
+
+    fn detect_with(vars: &[(&str, &str)]) -> f32 {
+        let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+        detect(|key| vars.get(key).map(|value| value.to_string()))
+    }
+
+    #[test]
+    fn should_detect_display_scale_from_env() {
+        assert_that!(detect_with(&[])).is_equal_to(1.0);
+        assert_that!(detect_with(&[("GDK_SCALE", "2")])).is_equal_to(2.0);
+        assert_that!(detect_with(&[("GDK_SCALE", "1"), ("GDK_DPI_SCALE", "1.5")])).is_equal_to(1.5);
+        assert_that!(detect_with(&[("QT_SCALE_FACTOR", "1.25")])).is_equal_to(1.25);
+        assert_that!(detect_with(&[("WINIT_X11_SCALE_FACTOR", "oops")])).is_equal_to(1.0);
+        assert_that!(detect_with(&[("GDK_SCALE", "0"), ("QT_SCALE_FACTOR", "2")])).is_equal_to(2.0);
+    }
+}
@@ -0,0 +1,216 @@
+use crate::app::state::PluginConfigCache;
+use pop_launcher_toolkit::launcher::{IconSource, SearchResult};
+use std::collections::HashMap;
+use std::ops::Range;
+
+// A line of the result list, either a section header or a search result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Line<'a> {
+    Header(&'a str),
+    Entry(usize),
+}
+
+// Header lines displayed before each group of results coming from the same plugin.
+// Headers are not entries: selection indexes are display positions and navigation skips them.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Sections {
+    // Display position of the first entry of each section and its label
+    headers: Vec<(usize, String)>,
+    // Index of the entry displayed at each position
+    order: Vec<usize>,
+}
+
+impl Sections {
+    // Entries with the same label are grouped together, sections are ordered by their
+    // first entry and entries keep their relative order within a section
+    pub fn new<I: IntoIterator<Item = String>>(labels: I) -> Self {
+        let labels: Vec<String> = labels.into_iter().collect();
+        let mut first_seen: HashMap<&str, usize> = HashMap::new();
+        for (idx, label) in labels.iter().enumerate() {
+            first_seen.entry(label.as_str()).or_insert(idx);
+        }
+
+        let mut order: Vec<usize> = (0..labels.len()).collect();
+        order.sort_by_key(|idx| first_seen[labels[*idx].as_str()]);
+
+        let mut headers: Vec<(usize, String)> = vec![];
+        for (position, idx) in order.iter().enumerate() {
+            let label = &labels[*idx];
+            if headers.last().map(|(_, last)| last) != Some(label) {
+                headers.push((position, label.clone()));
+            }
+        }
+
+        Sections { headers, order }
+    }
+
+    // Index of the entry displayed at `position`
+    pub fn entry(&self, position: usize) -> usize {
+        self.order.get(position).copied().unwrap_or(position)
+    }
+
+    // Number of lines displaying `len` entries and their headers
+    pub fn lines(&self, len: usize) -> usize {
+        len + self.headers.len()
+    }
+
+    // Line displaying the entry at position `idx`
+    pub fn line_of(&self, idx: usize) -> usize {
+        idx + self
+            .headers
+            .iter()
+            .take_while(|(start, _)| *start <= idx)
+            .count()
+    }
+
+    pub fn line(&self, line: usize) -> Line<'_> {
+        for (idx, (start, label)) in self.headers.iter().enumerate() {
+            // Each header is preceded by the headers of the previous sections
+            let header = start + idx;
+            if header == line {
+                return Line::Header(label);
+            } else if header > line {
+                return Line::Entry(line - idx);
+            }
+        }
+
+        Line::Entry(line - self.headers.len())
+    }
+
+    // Positions of the entries displayed on the given lines
+    pub fn entries(&self, lines: Range<usize>) -> Range<usize> {
+        self.entries_before(lines.start)..self.entries_before(lines.end)
+    }
+
+    fn entries_before(&self, line: usize) -> usize {
+        let headers = self
+            .headers
+            .iter()
+            .enumerate()
+            .take_while(|(idx, (start, _))| start + idx < line)
+            .count();
+
+        line - headers
+    }
+}
+
+// Results are matched to the plugin whose config icon is their category icon,
+// desktop entries don't have one
+pub fn label(result: &SearchResult, plugins: &PluginConfigCache) -> String {
+    let plugin = result
+        .category_icon
+        .as_ref()
+        .and_then(|icon| plugins.get_plugin_by_category(icon_name(icon)));
+
+    match plugin {
+        Some(plugin) => plugin_label(&plugin.name),
+        None => "Applications".to_string(),
+    }
+}
+
+pub fn icon_name(source: &IconSource) -> &str {
+    match source {
+        IconSource::Name(name) | IconSource::Mime(name) => name,
+    }
+}
+
+pub fn plugin_label(plugin_name: &str) -> String {
+    let label = match plugin_name {
+        "desktop_entries" => "Applications",
+        "files" | "find" => "Files",
+        "web" => "Web",
+        "recent" => "Recent",
+        "calc" => "Calculator",
+        "pop_shell" => "Windows",
+        "scripts" => "Scripts",
+        "terminal" => "Terminal",
+        name => {
+            let name = name.replace('_', " ");
+            let mut chars = name.chars();
+            return chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect())
+                .unwrap_or_default();
+        }
+    };
+
+    label.to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::sections::{plugin_label, Line, Sections};
+    use speculoos::prelude::*;
+
+    fn sections() -> Sections {
+        Sections::new(
+            ["Applications", "Applications", "Files", "Web", "Web"]
+                .iter()
+                .map(|label| label.to_string()),
+        )
+    }
+
+    #[test]
+    fn should_insert_a_header_before_each_section() {
+        let sections = sections();
+        let lines: Vec<Line> = (0..sections.lines(5))
+            .map(|line| sections.line(line))
+            .collect();
+
+        assert_that!(lines).is_equal_to(vec![
+            Line::Header("Applications"),
+            Line::Entry(0),
+            Line::Entry(1),
+            Line::Header("Files"),
+            Line::Entry(2),
+            Line::Header("Web"),
+            Line::Entry(3),
+            Line::Entry(4),
+        ]);
+        assert_that!(sections.line_of(2)).is_equal_to(4);
+        assert_that!(sections.line_of(4)).is_equal_to(7);
+    }
+
+    #[test]
+    fn should_map_visible_lines_to_entries() {
+        let sections = sections();
+
+        assert_that!(sections.entries(0..3)).is_equal_to(0..2);
+        assert_that!(sections.entries(3..6)).is_equal_to(2..3);
+        assert_that!(sections.entries(4..8)).is_equal_to(2..5);
+        assert_that!(Sections::default().entries(2..4)).is_equal_to(2..4);
+    }
+
+    #[test]
+    fn should_group_entries_by_label() {
+        let sections = Sections::new(
+            ["Files", "Applications", "Files", "Web", "Applications"]
+                .iter()
+                .map(|label| label.to_string()),
+        );
+        let lines: Vec<Line> = (0..sections.lines(5))
+            .map(|line| sections.line(line))
+            .collect();
+        let entries: Vec<usize> = (0..5).map(|position| sections.entry(position)).collect();
+
+        assert_that!(lines).is_equal_to(vec![
+            Line::Header("Files"),
+            Line::Entry(0),
+            Line::Entry(1),
+            Line::Header("Applications"),
+            Line::Entry(2),
+            Line::Entry(3),
+            Line::Header("Web"),
+            Line::Entry(4),
+        ]);
+        assert_that!(entries).is_equal_to(vec![0, 2, 1, 4, 3]);
+        assert_that!(Sections::default().entry(3)).is_equal_to(3);
+    }
+
+    #[test]
+    fn should_name_sections_after_plugins() {
+        assert_that!(plugin_label("desktop_entries")).is_equal_to("Applications".to_string());
+        assert_that!(plugin_label("find")).is_equal_to("Files".to_string());
+        assert_that!(plugin_label("pulse_audio")).is_equal_to("Pulse audio".to_string());
+    }
+}
@@ -1,12 +1,23 @@
+use crate::app::animation::Animations;
 use crate::app::cache::Cache;
+use crate::app::entries::calc_entry::CalcEntry;
+use crate::app::entries::emoji_entry::EmojiEntry;
 use crate::app::mode::ActiveMode;
 use crate::app::plugin_matchers::{match_web_plugins, Plugin};
+use crate::app::preview::Preview;
+use crate::app::sections::Sections;
+use crate::app::viewport::Viewport;
 use iced_native::widget::{scrollable, text_input};
 use log::debug;
-use pop_launcher_toolkit::launcher::SearchResult;
+use pop_launcher_toolkit::launcher::{ContextOption, SearchResult};
 
 use crate::app::{Message, INPUT_ID};
+use crate::bookmarks::Bookmark;
+use crate::engines::EngineQuery;
 use crate::icons::IconPath;
+use crate::scripts::ScriptEntry;
+use crate::ssh::SshHost;
+use crate::windows::Window;
 use crate::THEME;
 use std::collections::HashMap;
 
@@ -16,9 +27,116 @@ pub struct State<'a> {
     pub selected: Selection,
     pub cache: Cache<'a>,
     pub pop_search: Vec<SearchResult>,
+    // Section headers of the results, rebuilt when the results change
+    pub sections: Sections,
     pub scroll: scrollable::State,
+    pub viewport: Viewport,
     pub exec_on_next_search: bool,
+    pub search: SearchRequests,
     pub plugin_matchers: PluginConfigCache,
+    pub context_menu: Option<ContextMenu>,
+    pub preview: Option<Preview>,
+    pub calc: Option<CalcEntry>,
+    pub windows: Filtered<Window>,
+    pub ssh_hosts: Filtered<SshHost>,
+    pub emoji: Vec<EmojiEntry>,
+    pub bookmarks: Filtered<Bookmark>,
+    // Bookmarks are read from the browser databases in the background
+    pub bookmarks_loading: bool,
+    pub script_entries: Vec<ScriptEntry>,
+    // Incremented on each script run, output of previous runs is discarded
+    pub script_seq: u64,
+    pub engine_queries: Vec<EngineQuery>,
+    // Incremented on each suggestion request, like `script_seq`
+    pub engine_seq: u64,
+    // Last height requested with `--height: auto`
+    pub window_height: Option<u32>,
+    // Set when the window is first focused, it is mapped by then
+    pub mapped: bool,
+    pub animations: Animations,
+}
+
+// Items listed once when entering a built-in mode, then filtered on each keystroke
+#[derive(Debug)]
+pub struct Filtered<T> {
+    items: Option<Vec<T>>,
+    // Indices of the items matching the user input
+    matches: Vec<usize>,
+}
+
+impl<T> Default for Filtered<T> {
+    fn default() -> Self {
+        Filtered {
+            items: None,
+            matches: vec![],
+        }
+    }
+}
+
+impl<T> Filtered<T> {
+    pub fn is_loaded(&self) -> bool {
+        self.items.is_some()
+    }
+
+    pub fn filter<L, M>(&mut self, load: L, matches: M)
+    where
+        L: FnOnce() -> Vec<T>,
+        M: Fn(&T) -> bool,
+    {
+        let items = self.items.get_or_insert_with(load);
+        self.matches = items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| matches(item))
+            .map(|(idx, _)| idx)
+            .collect();
+    }
+
+    // Like `filter` but matches are sorted by descending score
+    pub fn rank<L, S>(&mut self, load: L, score: S)
+    where
+        L: FnOnce() -> Vec<T>,
+        S: Fn(&T) -> Option<i64>,
+    {
+        let items = self.items.get_or_insert_with(load);
+        let mut scored: Vec<(usize, i64)> = items
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, item)| score(item).map(|score| (idx, score)))
+            .collect();
+
+        // Stable sort, equal scores keep the loading order
+        scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+        self.matches = scored.into_iter().map(|(idx, _)| idx).collect();
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&T> {
+        let idx = *self.matches.get(idx)?;
+        self.items.as_ref()?.get(idx)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.matches
+            .iter()
+            .filter_map(move |idx| self.items.as_ref().and_then(|items| items.get(*idx)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.matches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.matches.is_empty()
+    }
+}
+
+// Context options pop-launcher returned for a search result,
+// they are displayed as a submenu in place of the search results.
+#[derive(Debug)]
+pub struct ContextMenu {
+    pub id: u32,
+    pub options: Vec<ContextOption>,
+    pub selected: usize,
 }
 
 #[derive(Debug, Default)]
@@ -31,9 +149,27 @@ impl PluginConfigCache {
         self.inner.get(plugin_name).and_then(|de| de.icon.clone())
     }
 
+    pub fn get_plugin_by_category(&self, icon_name: &str) -> Option<&Plugin> {
+        self.inner
+            .values()
+            .find(|plugin| plugin.category.as_deref() == Some(icon_name))
+    }
+
     pub fn insert(&mut self, key: String, plugin: Plugin) {
         self.inner.insert(key, plugin);
     }
+
+    // Script modes and web engines from the theme are matched like pop-launcher plugins
+    fn with_theme_modes() -> Self {
+        let mut cache = PluginConfigCache::default();
+        for mode in &THEME.script_modes {
+            cache.insert(mode.name.clone(), Plugin::from(mode));
+        }
+        for engine in &THEME.web_engines {
+            cache.insert(engine.name.clone(), Plugin::from(engine));
+        }
+        cache
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -49,6 +185,74 @@ pub enum Selection {
     PopLauncher(usize),
 }
 
+// Keeps track of the search requests sent to pop-launcher.
+// pop-launcher answers `Request::Search` with a `Response::Update` that doesn't tell which
+// query it belongs to. Only one search is sent at a time, tagged with the sequence number of
+// its query: its update is displayed if no other query was typed meanwhile, otherwise it is
+// dropped and the latest query, kept waiting, is sent.
+#[derive(Debug, Default)]
+pub struct SearchRequests {
+    // Sequence number of the latest query typed by the user
+    latest: u64,
+    // Sequence number of the search waiting for its update
+    in_flight: Option<u64>,
+    // The latest query was typed while a search was in flight
+    queued: bool,
+    // The latest query is waiting for its debounce delay to elapse
+    debouncing: bool,
+}
+
+impl SearchRequests {
+    // Delay the latest query, returns its sequence number
+    pub fn debounce(&mut self) -> u64 {
+        self.latest += 1;
+        self.debouncing = true;
+        self.latest
+    }
+
+    // Returns true if no other query was typed during the debounce delay
+    pub fn on_debounce_elapsed(&mut self, seq: u64) -> bool {
+        if seq == self.latest {
+            self.debouncing = false;
+            true
+        } else {
+            false
+        }
+    }
+
+    // Returns true if the search can be sent now, false if it waits for the search in flight
+    pub fn on_search(&mut self) -> bool {
+        // Any pending debounced query is now outdated
+        self.latest += 1;
+        self.debouncing = false;
+        if self.in_flight.is_some() {
+            self.queued = true;
+            false
+        } else {
+            self.in_flight = Some(self.latest);
+            true
+        }
+    }
+
+    // Returns the update if it answers the latest query.
+    // Updates sent by pop-launcher on its own, with no search in flight, are accepted.
+    pub fn on_response(&mut self, update: Vec<SearchResult>) -> Option<Vec<SearchResult>> {
+        match self.in_flight.take() {
+            Some(seq) if seq != self.latest => None,
+            _ => Some(update),
+        }
+    }
+
+    // Returns true once if a query is waiting to be sent
+    pub fn take_queued(&mut self) -> bool {
+        std::mem::take(&mut self.queued)
+    }
+
+    pub fn is_searching(&self) -> bool {
+        self.debouncing || self.queued || self.in_flight.is_some()
+    }
+}
+
 impl State<'_> {
     pub fn get_active_mode(&self) -> &ActiveMode {
         &self.input_value.mode
@@ -89,7 +293,15 @@ impl State<'_> {
                     .find_map(|match_| match_)
             });
 
-            if let Some(query_data) = plugin_split {
+            if let Some((prefix, mode, query)) = ActiveMode::from_builtin_prefix(input) {
+                self.input_value.modifier_display = prefix.to_string();
+                self.input_value.mode = mode;
+                self.input_value.input_display = if THEME.plugin_hint().is_none() {
+                    input.to_string()
+                } else {
+                    query.to_string()
+                };
+            } else if let Some(query_data) = plugin_split {
                 self.input_value.modifier_display = query_data.modifier.clone();
                 self.input_value.mode = ActiveMode::from(query_data.clone());
                 // If plugin-hint is disabled use the full input,
@@ -110,16 +322,20 @@ impl State<'_> {
             }
         };
 
+        let input = &self.input_value.input_display;
         let pop_query = match &self.input_value.mode {
-            ActiveMode::History | ActiveMode::DesktopEntry => {
-                self.input_value.input_display.clone()
-            }
-            ActiveMode::Web { modifier, .. } => {
-                format!("{modifier} {}", self.input_value.input_display)
-            }
-            ActiveMode::Plugin { modifier, .. } => {
-                format!("{modifier}{}", self.input_value.input_display)
-            }
+            ActiveMode::History | ActiveMode::DesktopEntry => input.clone(),
+            ActiveMode::Web { modifier, .. } => format!("{modifier} {input}"),
+            ActiveMode::Plugin { modifier, .. } => format!("{modifier}{input}"),
+            ActiveMode::Script { modifier, .. } | ActiveMode::WebEngine { modifier, .. } => input
+                .strip_prefix(modifier.as_str())
+                .unwrap_or(input)
+                .trim_start()
+                .to_string(),
+            builtin => input
+                .strip_prefix(builtin.builtin_prefix().unwrap_or_default())
+                .unwrap_or(input)
+                .to_string(),
         };
 
         self.input_value.pop_query = pop_query;
@@ -144,10 +360,113 @@ impl Default for State<'_> {
             selected: Selection::History(0),
             cache: Default::default(),
             pop_search: Default::default(),
+            sections: Sections::default(),
             scroll: Default::default(),
+            viewport: Viewport::grid(THEME.visible_rows(), THEME.app_container.rows.columns()),
             input_value: SearchInput::default(),
             exec_on_next_search: false,
-            plugin_matchers: PluginConfigCache::default(),
+            search: SearchRequests::default(),
+            plugin_matchers: PluginConfigCache::with_theme_modes(),
+            context_menu: None,
+            preview: None,
+            calc: None,
+            windows: Filtered::default(),
+            ssh_hosts: Filtered::default(),
+            emoji: vec![],
+            bookmarks: Filtered::default(),
+            bookmarks_loading: false,
+            script_entries: vec![],
+            script_seq: 0,
+            engine_queries: vec![],
+            engine_seq: 0,
+            window_height: None,
+            mapped: false,
+            animations: Animations::default(),
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::app::state::{Filtered, SearchRequests};
+    use speculoos::prelude::*;
+
+    #[test]
+    fn should_only_display_the_update_of_the_latest_query() {
+        let mut search = SearchRequests::default();
+
+        asserting!("First search is sent")
+            .that(&search.on_search())
+            .is_true();
+        asserting!("Second search waits for the first one")
+            .that(&search.on_search())
+            .is_false();
+        asserting!("Update of the first search is outdated")
+            .that(&search.on_response(vec![]))
+            .is_none();
+        assert_that!(search.take_queued()).is_true();
+        assert_that!(search.take_queued()).is_false();
+
+        assert_that!(search.on_search()).is_true();
+        assert_that!(search.is_searching()).is_true();
+        asserting!("Update of the latest search is displayed")
+            .that(&search.on_response(vec![]))
+            .is_some();
+        assert_that!(search.is_searching()).is_false();
+    }
+
+    #[test]
+    fn should_accept_updates_without_search_in_flight() {
+        let mut search = SearchRequests::default();
+
+        assert_that!(search.on_response(vec![])).is_some();
+        assert_that!(search.take_queued()).is_false();
+    }
+
+    #[test]
+    fn should_drop_outdated_debounced_query() {
+        let mut search = SearchRequests::default();
+
+        let first = search.debounce();
+        let second = search.debounce();
+
+        assert_that!(search.on_debounce_elapsed(first)).is_false();
+        assert_that!(search.is_searching()).is_true();
+        assert_that!(search.on_debounce_elapsed(second)).is_true();
+        assert_that!(search.is_searching()).is_false();
+    }
+
+    #[test]
+    fn should_cancel_debounced_query_on_search() {
+        let mut search = SearchRequests::default();
+
+        let debounced = search.debounce();
+        search.on_search();
+
+        assert_that!(search.on_debounce_elapsed(debounced)).is_false();
+    }
+
+    #[test]
+    fn should_load_items_once_and_filter_them() {
+        let mut hosts = Filtered::default();
+
+        hosts.filter(|| vec!["web", "db", "backup"], |host| host.contains('b'));
+        hosts.filter(|| unreachable!(), |host| host.starts_with('b'));
+
+        assert_that!(hosts.len()).is_equal_to(1);
+        assert_that!(hosts.get(0)).is_equal_to(Some(&"backup"));
+        assert_that!(hosts.get(1)).is_none();
+    }
+
+    #[test]
+    fn should_rank_items_by_score() {
+        let mut pages = Filtered::default();
+
+        pages.rank(
+            || vec!["rust", "rustup", "python"],
+            |page| page.strip_prefix("rust").map(|rest| 10 - rest.len() as i64),
+        );
+
+        assert_that!(pages.iter().copied().collect::<Vec<_>>()).is_equal_to(vec!["rust", "rustup"]);
+    }
+}
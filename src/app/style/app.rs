@@ -1,3 +1,4 @@
+use crate::app::style::rows::generic::GenericContainerStyle;
 use crate::app::style::scrollable::scroller::ScrollerStyles;
 use crate::app::style::scrollable::RowContainerStyle;
 use crate::app::style::search::SearchContainerStyles;
@@ -27,6 +28,7 @@ pub struct AppContainerStyles {
     pub search: SearchContainerStyles,
     pub rows: RowContainerStyle,
     pub scrollable: ScrollerStyles,
+    pub preview: Option<GenericContainerStyle>,
 }
 
 impl StyleSheet for &AppContainerStyles {
@@ -55,6 +57,7 @@ impl Default for AppContainerStyles {
             search: Default::default(),
             rows: Default::default(),
             scrollable: Default::default(),
+            preview: None,
         }
     }
 }
@@ -1,12 +1,21 @@
+use crate::app::animation::Transition;
+use crate::app::clipboard::CopyField;
+use crate::app::placement::Placement;
 use crate::app::style::app::AppContainerStyles;
 use crate::app::style::rows::generic::GenericContainerStyle;
 use crate::app::style::scrollable::scroller::ScrollerStyles;
+use crate::app::style::scrollable::RowLayout;
 use crate::app::style::search::input::SearchInputStyles;
 use crate::app::style::search::SearchContainerStyles;
 use crate::config::color::OnagreColor;
 use crate::config::padding::OnagrePadding;
+use crate::emoji::{EmojiAction, SkinTone};
+use crate::engines::WebEngine;
+use crate::font;
+use crate::scripts::ScriptMode;
 use iced::widget::container::Appearance;
-use iced::Background;
+use iced::{Background, Length};
+use std::collections::HashMap;
 
 pub mod app;
 pub mod rows;
@@ -41,11 +50,47 @@ impl AsRef<Theme> for Theme {
 pub struct Theme {
     // Layout
     pub exit_unfocused: bool,
+    pub search_debounce: u32,
+    pub debounced_plugins: Vec<String>,
+    // Maximum number of rows displayed for a given mode
+    pub max_results: HashMap<String, u32>,
+    // Which part of the selected row is copied to the clipboard for a given mode
+    pub copy_fields: HashMap<String, CopyField>,
+    // Command prefix used to run a program in a terminal, e.g. `alacritty -e`
+    pub terminal: Option<String>,
+    // Skin tone applied to emoji supporting it
+    pub skin_tone: Option<SkinTone>,
+    pub emoji_action: EmojiAction,
+    // Search input placeholder for a given mode or plugin prefix
+    pub placeholders: HashMap<String, String>,
+    // Label displayed before the search input
+    pub prompt: Option<String>,
+    // Displayed when nothing matches the query, `{query}` is replaced with the user input
+    pub empty_message: String,
+    // Custom modes declared with `.script-mode` blocks
+    pub script_modes: Vec<ScriptMode>,
+    // Search engines declared with `.web-engine` blocks
+    pub web_engines: Vec<WebEngine>,
     pub size: (u32, u32),
+    // With `--height: auto` the window fits the displayed rows, `height` is used as the maximum
+    // unless `--max-rows` is set
+    pub auto_height: bool,
+    pub min_rows: u32,
+    pub max_rows: u32,
     pub font: Option<String>,
     pub font_size: u16,
-    pub icon_theme: Option<String>,
+    // Icon themes searched in order before the themes they inherit from and hicolor
+    pub icon_theme: Option<Vec<String>>,
+    // Icon name used when an entry has no icon, for a given entry type
+    pub fallback_icons: HashMap<String, String>,
     pub icon_size: u16,
+    // Multiplies every size of the theme, on top of the display scale factor
+    pub scale: f32,
+    // Where the window is opened and whether it stays above other windows
+    pub placement: Placement,
+    // Selection, scrolling and window fade animations, disabled by `--reduce-motion`
+    pub transition: Option<Transition>,
+    pub reduce_motion: bool,
     pub padding: OnagrePadding,
 
     // Style
@@ -72,6 +117,10 @@ impl Theme {
         self.app_container.search.plugin_hint.as_ref()
     }
 
+    pub fn preview(&self) -> Option<&GenericContainerStyle> {
+        self.app_container.preview.as_ref()
+    }
+
     pub fn scrollable(&self) -> &ScrollerStyles {
         &self.app_container.scrollable
     }
@@ -79,18 +128,206 @@ impl Theme {
     pub fn app(&self) -> &AppContainerStyles {
         &self.app_container
     }
+
+    pub fn transition(&self) -> Option<&Transition> {
+        self.transition.as_ref().filter(|_| !self.reduce_motion)
+    }
+
+    pub fn max_results(&self, mode: &str) -> usize {
+        self.max_results
+            .get(mode)
+            .map(|max| *max as usize)
+            .unwrap_or(usize::MAX)
+    }
+
+    pub fn placeholder(&self, mode: &str) -> Option<&str> {
+        self.placeholders.get(mode.trim()).map(String::as_str)
+    }
+
+    pub fn empty_message(&self, query: &str) -> String {
+        self.empty_message.replace("{query}", query.trim())
+    }
+
+    pub fn copy_field(&self, mode: &str) -> CopyField {
+        self.copy_fields
+            .get(mode)
+            .copied()
+            .unwrap_or(CopyField::Name)
+    }
+
+    pub fn script_mode(&self, name: &str) -> Option<&ScriptMode> {
+        self.script_modes.iter().find(|mode| mode.name == name)
+    }
+
+    pub fn web_engine(&self, name: &str) -> Option<&WebEngine> {
+        self.web_engines.iter().find(|engine| engine.name == name)
+    }
+
+    // Defaults to `$TERMINAL -e`, then to `x-terminal-emulator -e`
+    pub fn terminal(&self) -> Vec<String> {
+        let terminal = self
+            .terminal
+            .clone()
+            .or_else(|| {
+                std::env::var("TERMINAL")
+                    .ok()
+                    .map(|terminal| format!("{terminal} -e"))
+            })
+            .unwrap_or_else(|| "x-terminal-emulator -e".to_string());
+
+        shell_words::split(&terminal).unwrap_or_default()
+    }
+
+    // Number of rows displayed at once, unless set with `--lines`
+    // we guess it from the window height and the row layout
+    pub fn visible_rows(&self) -> usize {
+        let rows = &self.app_container.rows;
+        if self.auto_height && self.max_rows > 0 {
+            return self.max_rows as usize;
+        }
+
+        if rows.lines > 0 {
+            return rows.lines as usize;
+        }
+
+        let portion = |length: Length| match length {
+            Length::Fill => Some(1),
+            Length::FillPortion(portion) => Some(portion),
+            _ => None,
+        };
+
+        let search = self.search();
+        let inner_height =
+            self.size.1 as f32 - self.padding.vertical() - self.app_container.padding.vertical();
+
+        let rows_height = match (search.height, rows.height) {
+            (_, Length::Fixed(height)) => height,
+            (Length::Fixed(height), _) => inner_height - height,
+            (search_height, rows_height) => match (portion(search_height), portion(rows_height)) {
+                (Some(search_portion), Some(rows_portion)) => {
+                    inner_height * rows_portion as f32 / (search_portion + rows_portion) as f32
+                }
+                _ => inner_height - self.search_bar_height(),
+            },
+        };
+
+        // Rows with a description are the tallest, all of them must fit
+        ((rows_height - rows.padding.vertical()) / self.row_height(true)).max(1.0) as usize
+    }
+
+    // Window height fitting the search bar and lines of the given heights,
+    // `None` unless `--height: auto` is set
+    pub fn auto_height(&self, lines: &[f32]) -> Option<u32> {
+        if !self.auto_height {
+            return None;
+        }
+
+        let max_rows = self.visible_rows();
+        let lines = &lines[..lines.len().min(max_rows)];
+        // Missing lines up to `--min-rows` are left empty
+        let empty_lines = (self.min_rows as usize)
+            .min(max_rows)
+            .saturating_sub(lines.len());
+        let rows_height = if lines.is_empty() && empty_lines == 0 {
+            0.0
+        } else {
+            lines.iter().sum::<f32>()
+                + empty_lines as f32 * self.row_height(false)
+                + self.app_container.rows.padding.vertical()
+        };
+
+        let height = self.padding.vertical()
+            + self.app_container.padding.vertical()
+            + self.search_bar_height()
+            + rows_height;
+
+        Some(height.ceil() as u32)
+    }
+
+    // Portions can't be used when the window fits its content
+    pub fn search_height(&self) -> Length {
+        if self.auto_height {
+            Length::Fixed(self.search_bar_height())
+        } else {
+            self.search().height
+        }
+    }
+
+    pub fn rows_height(&self) -> Length {
+        if self.auto_height {
+            Length::Fill
+        } else {
+            self.app_container.rows.height
+        }
+    }
+
+    fn search_bar_height(&self) -> f32 {
+        let search = self.search();
+        match search.height {
+            Length::Fixed(height) => height,
+            _ => {
+                font::line_height(&search.input.font, f32::from(search.input.font_size))
+                    + search.input.padding.vertical()
+                    + search.padding.vertical()
+            }
+        }
+    }
+
+    // Height of a line of entries, `description` tells if the entries have one
+    pub fn row_height(&self, description: bool) -> f32 {
+        let rows = &self.app_container.rows;
+        match rows.layout {
+            RowLayout::List => rows
+                .row
+                .estimated_height(description)
+                .max(rows.row_selected.estimated_height(description)),
+            RowLayout::Grid => rows
+                .row
+                .estimated_cell_height()
+                .max(rows.row_selected.estimated_cell_height()),
+        }
+    }
 }
 
 impl Default for Theme {
     fn default() -> Self {
         Self {
             exit_unfocused: false,
+            // Slow plugins are only queried once the user stops typing
+            search_debounce: 150,
+            debounced_plugins: vec!["find".to_string(), "recent".to_string()],
+            max_results: HashMap::new(),
+            copy_fields: HashMap::from([("web".to_string(), CopyField::Query)]),
+            terminal: None,
+            skin_tone: None,
+            emoji_action: EmojiAction::Copy,
+            placeholders: HashMap::new(),
+            prompt: None,
+            empty_message: "No results for “{query}”".to_string(),
+            script_modes: vec![],
+            web_engines: vec![],
             size: (450, 300),
+            auto_height: false,
+            min_rows: 0,
+            max_rows: 0,
             font: None,
             font_size: 18,
             // TODO: default icon theme ?
-            icon_theme: Some("Papirus".to_string()),
+            icon_theme: Some(vec!["Papirus".to_string()]),
+            fallback_icons: HashMap::from([
+                (
+                    "desktop".to_string(),
+                    "application-x-executable".to_string(),
+                ),
+                ("window".to_string(), "application-x-executable".to_string()),
+                ("web".to_string(), "web-browser".to_string()),
+                ("bookmark".to_string(), "web-browser".to_string()),
+            ]),
             icon_size: 24,
+            scale: 1.0,
+            placement: Placement::default(),
+            transition: None,
+            reduce_motion: false,
             background: OnagreColor::DEFAULT_BACKGROUND,
             color: OnagreColor::DEFAULT_TEXT,
             border_color: OnagreColor::TRANSPARENT,
@@ -1,5 +1,7 @@
 use crate::config::color::OnagreColor;
+use crate::config::font::OnagreFont;
 use crate::config::padding::OnagrePadding;
+use crate::font;
 use iced::alignment::{Horizontal, Vertical};
 use iced::Length;
 use iced_core::Background;
@@ -16,6 +18,7 @@ pub struct GenericContainerStyle {
 
     // Layout
     pub font_size: u16,
+    pub font: OnagreFont,
     pub padding: OnagrePadding,
     pub align_x: Horizontal,
     pub align_y: Vertical,
@@ -37,6 +40,7 @@ impl Default for GenericContainerStyle {
             align_x: Horizontal::Left,
             align_y: Vertical::Center,
             font_size: 14,
+            font: OnagreFont::default(),
         }
     }
 }
@@ -56,12 +60,59 @@ impl StyleSheet for &GenericContainerStyle {
 }
 
 impl GenericContainerStyle {
+    // Height of a container holding a single line of text
+    pub fn estimated_height(&self) -> f32 {
+        match self.height {
+            Length::Fixed(height) => height,
+            _ => font::line_height(&self.font, f32::from(self.font_size)) + self.padding.vertical(),
+        }
+    }
+
     pub fn description_default() -> Self {
         Self {
             font_size: 11,
             ..Default::default()
         }
     }
+
+    pub fn preview_default() -> Self {
+        Self {
+            font_size: 12,
+            width: Length::FillPortion(1),
+            height: Length::Fill,
+            align_y: Vertical::Top,
+            padding: OnagrePadding::from(10),
+            ..Default::default()
+        }
+    }
+
+    pub fn section_header_default() -> Self {
+        Self {
+            font_size: 11,
+            padding: OnagrePadding {
+                top: 6,
+                right: 5,
+                bottom: 2,
+                left: 5,
+            },
+            ..Default::default()
+        }
+    }
+
+    pub fn shortcut_default() -> Self {
+        Self {
+            font_size: 11,
+            width: Length::Shrink,
+            align_x: Horizontal::Center,
+            padding: OnagrePadding {
+                top: 0,
+                right: 4,
+                bottom: 0,
+                left: 4,
+            },
+            ..Default::default()
+        }
+    }
 }
 
 impl Eq for GenericContainerStyle {}
@@ -27,12 +27,14 @@ pub struct RowStyles {
     pub border_color: OnagreColor,
     pub hide_description: bool,
     pub hide_category_icon: bool,
+    pub hide_shortcut: bool,
 
     // Children
     pub title: GenericContainerStyle,
     pub description: GenericContainerStyle,
     pub icon: IconStyle,
     pub category_icon: IconStyle,
+    pub shortcut: GenericContainerStyle,
 }
 
 impl StyleSheet for &RowStyles {
@@ -49,6 +51,30 @@ impl StyleSheet for &RowStyles {
     }
 }
 
+// A row moving from one style to another, only the row container is blended
+pub struct RowTransition {
+    pub from: &'static RowStyles,
+    pub to: &'static RowStyles,
+    pub progress: f32,
+}
+
+impl StyleSheet for RowTransition {
+    type Style = iced::Theme;
+
+    fn appearance(&self, _: &Self::Style) -> Appearance {
+        let (from, to, progress) = (self.from, self.to, self.progress);
+        Appearance {
+            text_color: Some(from.color.mix(to.color, progress).into()),
+            background: Some(Background::Color(
+                from.background.mix(to.background, progress).into(),
+            )),
+            border_radius: from.border_radius + (to.border_radius - from.border_radius) * progress,
+            border_width: from.border_width + (to.border_width - from.border_width) * progress,
+            border_color: from.border_color.mix(to.border_color, progress).into(),
+        }
+    }
+}
+
 impl Default for RowStyles {
     fn default() -> Self {
         RowStyles {
@@ -64,10 +90,12 @@ impl Default for RowStyles {
             border_color: OnagreColor::RED,
             hide_description: false,
             hide_category_icon: false,
+            hide_shortcut: false,
             title: GenericContainerStyle::default(),
             description: GenericContainerStyle::description_default(),
             icon: Default::default(),
             category_icon: IconStyle::category_default(),
+            shortcut: GenericContainerStyle::shortcut_default(),
             spacing: 2,
         }
     }
@@ -85,8 +113,41 @@ impl RowStyles {
                 color: OnagreColor::WHITE,
                 ..GenericContainerStyle::description_default()
             },
+            shortcut: GenericContainerStyle {
+                color: OnagreColor::WHITE,
+                ..GenericContainerStyle::shortcut_default()
+            },
             ..Default::default()
         }
     }
+
+    // Height of a row, used to guess how many rows fit in the window.
+    // The description line is only displayed for entries having one.
+    pub fn estimated_height(&self, description: bool) -> f32 {
+        if let Length::Fixed(height) = self.height {
+            return height;
+        }
+
+        let mut content_height = self.title.estimated_height();
+        if description && !self.hide_description {
+            content_height += self.description.estimated_height();
+        }
+
+        let icon_height = self.icon.icon_size as f32 + self.icon.padding.vertical();
+
+        content_height.max(icon_height) + self.padding.vertical()
+    }
+
+    // Grid cells stack the icon above the title
+    pub fn estimated_cell_height(&self) -> f32 {
+        if let Length::Fixed(height) = self.height {
+            return height;
+        }
+
+        let icon_height = self.icon.icon_size as f32 + self.icon.padding.vertical();
+
+        icon_height + self.spacing as f32 + self.title.estimated_height() + self.padding.vertical()
+    }
 }
+
 impl Eq for RowStyles {}
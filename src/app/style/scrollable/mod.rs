@@ -1,3 +1,4 @@
+use crate::app::style::rows::generic::GenericContainerStyle;
 use crate::app::style::rows::RowStyles;
 use crate::config::color::OnagreColor;
 use crate::config::padding::OnagrePadding;
@@ -7,6 +8,13 @@ use iced_style::container::{Appearance, StyleSheet};
 
 pub mod scroller;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowLayout {
+    List,
+    // Large icons with the title underneath, `columns` entries per row
+    Grid,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct RowContainerStyle {
     // Iced Container
@@ -20,14 +28,31 @@ pub struct RowContainerStyle {
     pub padding: OnagrePadding,
     pub width: Length,
     pub height: Length,
+    // Number of rows displayed at once, guessed from the layout when zero
+    pub lines: u16,
+    pub layout: RowLayout,
+    pub columns: u16,
+    // Group pop-launcher results by plugin under section headers
+    pub group_results: bool,
 
     // Iced Scrollable
     pub row: RowStyles,
     pub row_selected: RowStyles,
+    pub section_header: GenericContainerStyle,
 }
 
 impl Eq for RowContainerStyle {}
 
+impl RowContainerStyle {
+    // Entries displayed on each row
+    pub fn columns(&self) -> usize {
+        match self.layout {
+            RowLayout::List => 1,
+            RowLayout::Grid => self.columns.max(1) as usize,
+        }
+    }
+}
+
 impl StyleSheet for &RowContainerStyle {
     type Style = iced::Theme;
 
@@ -58,8 +83,13 @@ impl Default for RowContainerStyle {
             },
             width: Length::Fill,
             height: Length::FillPortion(8),
+            lines: 0,
+            layout: RowLayout::List,
+            columns: 5,
+            group_results: false,
             row: RowStyles::default(),
             row_selected: RowStyles::default_selected(),
+            section_header: GenericContainerStyle::section_header_default(),
         }
     }
 }
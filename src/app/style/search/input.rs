@@ -1,4 +1,5 @@
 use crate::config::color::OnagreColor;
+use crate::config::font::OnagreFont;
 use crate::config::padding::OnagrePadding;
 use iced::alignment::{Horizontal, Vertical};
 use iced::Length;
@@ -19,6 +20,7 @@ pub struct SearchInputStyles {
 
     // Layout
     pub font_size: u16,
+    pub font: OnagreFont,
     pub width: Length,
     pub height: Length,
     pub align_x: Horizontal,
@@ -84,6 +86,7 @@ impl Default for SearchInputStyles {
             selection_color: OnagreColor::DEFAULT_BORDER,
             text_width: Length::Fill,
             font_size: 14,
+            font: OnagreFont::default(),
             width: Length::Fill,
             height: Length::Fill,
             align_x: Horizontal::Left,
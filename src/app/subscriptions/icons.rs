@@ -0,0 +1,30 @@
+use iced_native::futures::stream::BoxStream;
+use iced_native::Subscription;
+
+use crate::icons::pipeline;
+use std::hash::Hash;
+
+// Emits each time the icon pipeline finished converting an icon
+pub struct IconPipelineSubscription;
+
+impl IconPipelineSubscription {
+    pub fn create() -> Subscription<()> {
+        Subscription::from_recipe(IconPipelineSubscription)
+    }
+}
+
+impl<H, I> iced_native::subscription::Recipe<H, I> for IconPipelineSubscription
+where
+    H: std::hash::Hasher,
+{
+    type Output = ();
+
+    fn hash(&self, state: &mut H) {
+        std::any::TypeId::of::<Self>().hash(state);
+        "IconPipelineSubscription".hash(state)
+    }
+
+    fn stream(self: Box<Self>, _: BoxStream<I>) -> BoxStream<Self::Output> {
+        Box::pin(pipeline::ready_notifications())
+    }
+}
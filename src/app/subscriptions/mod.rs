@@ -1,2 +1,3 @@
+pub mod icons;
 pub mod plugin_configs;
 pub mod pop_launcher;
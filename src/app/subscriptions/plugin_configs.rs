@@ -1,4 +1,5 @@
 use crate::app::plugin_matchers::Plugin;
+use crate::app::sections::icon_name;
 use iced::futures::StreamExt;
 use iced_native::futures::stream::BoxStream;
 use iced_native::Subscription;
@@ -48,9 +49,14 @@ where
                 Plugin {
                     name,
                     icon,
+                    category: config
+                        .icon
+                        .as_ref()
+                        .map(|source| icon_name(source).to_string()),
                     history: config.history,
                     help: config.query.help.map(|h| h.to_string()),
                     regex,
+                    prefix_only: false,
                 }
             }),
         )
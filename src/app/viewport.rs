@@ -0,0 +1,224 @@
+use std::ops::Range;
+
+// The window of rows currently displayed, only those are laid out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Viewport {
+    // Index of the first visible row
+    pub offset: usize,
+    // Number of rows displayed at once
+    pub lines: usize,
+    // Entries per row, more than one with the grid layout
+    pub columns: usize,
+}
+
+impl Viewport {
+    pub fn grid(lines: usize, columns: usize) -> Self {
+        Self {
+            offset: 0,
+            lines: lines.max(1),
+            columns: columns.max(1),
+        }
+    }
+
+    // Number of entries displayed at once
+    pub fn page(&self) -> usize {
+        self.lines * self.columns
+    }
+
+    pub fn reset(&mut self) {
+        self.offset = 0;
+    }
+
+    // Scroll just enough to keep the selected row fully in view
+    pub fn follow(&mut self, selected: usize) {
+        let selected = selected / self.columns;
+        if selected < self.offset {
+            self.offset = selected;
+        } else if selected >= self.offset + self.lines {
+            self.offset = selected + 1 - self.lines;
+        }
+    }
+
+    // The entry `step` entries after `selected`, or the last one when the row below is
+    // not complete
+    pub fn advance(&self, selected: usize, step: usize, len: usize) -> usize {
+        let last = len.saturating_sub(1);
+        if selected + step <= last {
+            selected + step
+        } else if selected / self.columns < last / self.columns {
+            last
+        } else {
+            selected
+        }
+    }
+
+    // Indexes of the entries visible from the `previous` offset to the current one
+    pub fn span(&self, previous: usize, len: usize) -> Range<usize> {
+        let start = (previous.min(self.offset) * self.columns).min(len);
+        let end = ((previous.max(self.offset) + self.lines) * self.columns).min(len);
+        start..end
+    }
+
+    // Indexes of the visible entries for a list of `len` entries
+    pub fn range(&self, len: usize) -> Range<usize> {
+        let start = (self.offset * self.columns).min(len);
+        let end = ((self.offset + self.lines) * self.columns).min(len);
+        start..end
+    }
+}
+
+// The number displayed on the badge of the visible entry at `position`,
+// `Alt+<number>` activates the entry
+pub fn shortcut(position: usize) -> Option<usize> {
+    if position < 9 {
+        Some(position + 1)
+    } else {
+        None
+    }
+}
+
+// The entry displaying the `shortcut` badge among the `visible` entries
+pub fn shortcut_entry(visible: Range<usize>, shortcut: usize) -> Option<usize> {
+    let idx = visible.start + shortcut.checked_sub(1)?;
+    if idx < visible.end {
+        Some(idx)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::sections::Sections;
+    use crate::app::viewport::{shortcut, shortcut_entry, Viewport};
+    use speculoos::prelude::*;
+
+    #[test]
+    fn should_scroll_down_to_selected_row() {
+        let mut viewport = Viewport::grid(5, 1);
+
+        viewport.follow(7);
+
+        assert_that!(viewport.offset).is_equal_to(3);
+        assert_that!(viewport.range(20)).is_equal_to(3..8);
+    }
+
+    #[test]
+    fn should_scroll_up_to_selected_row() {
+        let mut viewport = Viewport {
+            offset: 10,
+            lines: 5,
+            columns: 1,
+        };
+
+        viewport.follow(4);
+
+        assert_that!(viewport.offset).is_equal_to(4);
+    }
+
+    #[test]
+    fn should_not_scroll_when_selected_row_is_visible() {
+        let mut viewport = Viewport {
+            offset: 2,
+            lines: 5,
+            columns: 1,
+        };
+
+        viewport.follow(6);
+
+        assert_that!(viewport.offset).is_equal_to(2);
+    }
+
+    #[test]
+    fn should_clamp_range_to_entries() {
+        let viewport = Viewport {
+            offset: 2,
+            lines: 5,
+            columns: 1,
+        };
+
+        assert_that!(viewport.range(4)).is_equal_to(2..4);
+        assert_that!(viewport.range(1)).is_equal_to(1..1);
+    }
+
+    #[test]
+    fn should_span_rows_while_scrolling() {
+        let viewport = Viewport {
+            offset: 4,
+            lines: 5,
+            columns: 1,
+        };
+
+        assert_that!(viewport.span(3, 20)).is_equal_to(3..9);
+        assert_that!(viewport.span(6, 20)).is_equal_to(4..11);
+        assert_that!(viewport.span(6, 10)).is_equal_to(4..10);
+    }
+
+    #[test]
+    fn should_scroll_grid_by_whole_rows() {
+        let mut viewport = Viewport::grid(2, 5);
+
+        viewport.follow(12);
+
+        assert_that!(viewport.offset).is_equal_to(1);
+        assert_that!(viewport.page()).is_equal_to(10);
+        assert_that!(viewport.range(13)).is_equal_to(5..13);
+        assert_that!(viewport.span(0, 20)).is_equal_to(0..15);
+    }
+
+    #[test]
+    fn should_move_down_to_an_incomplete_grid_row() {
+        let viewport = Viewport::grid(2, 5);
+
+        assert_that!(viewport.advance(1, 5, 13)).is_equal_to(6);
+        assert_that!(viewport.advance(8, 5, 13)).is_equal_to(12);
+        assert_that!(viewport.advance(11, 5, 13)).is_equal_to(11);
+        assert_that!(Viewport::grid(5, 1).advance(3, 1, 4)).is_equal_to(3);
+    }
+
+    #[test]
+    fn should_map_shortcuts_to_visible_entries() {
+        let viewport = Viewport {
+            offset: 3,
+            lines: 5,
+            columns: 1,
+        };
+        let visible = viewport.range(20);
+
+        assert_that!(shortcut_entry(visible.clone(), 1)).is_equal_to(Some(3));
+        assert_that!(shortcut_entry(visible.clone(), 5)).is_equal_to(Some(7));
+        // Only the visible rows have a badge
+        assert_that!(shortcut_entry(visible.clone(), 6)).is_none();
+        assert_that!(shortcut_entry(visible, 0)).is_none();
+        // The last rows don't fill the viewport
+        assert_that!(shortcut_entry(viewport.range(5), 2)).is_equal_to(Some(4));
+        assert_that!(shortcut_entry(viewport.range(5), 3)).is_none();
+
+        for idx in 3..8 {
+            let badge = shortcut(idx - 3).unwrap();
+            assert_that!(shortcut_entry(viewport.range(20), badge)).is_equal_to(Some(idx));
+        }
+        assert_that!(shortcut(9)).is_none();
+    }
+
+    #[test]
+    fn should_not_count_section_headers_in_shortcuts() {
+        let sections = Sections::new(
+            ["Applications", "Applications", "Files", "Files"]
+                .iter()
+                .map(|label| label.to_string()),
+        );
+        let viewport = Viewport {
+            offset: 2,
+            lines: 3,
+            columns: 1,
+        };
+
+        // Lines 2..5 display the second application, the files header and the first file
+        let visible = sections.entries(viewport.range(sections.lines(4)));
+
+        assert_that!(shortcut_entry(visible.clone(), 1)).is_equal_to(Some(1));
+        assert_that!(shortcut_entry(visible.clone(), 2)).is_equal_to(Some(2));
+        assert_that!(shortcut_entry(visible, 3)).is_none();
+    }
+}
@@ -0,0 +1,168 @@
+use crate::bookmarks::{profiles, store_favicon, Bookmark, Snapshot};
+use log::warn;
+use rusqlite::OptionalExtension;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const FAVICON_QUERY: &str = "
+    SELECT b.image_data
+    FROM icon_mapping m
+    JOIN favicon_bitmaps b ON b.icon_id = m.icon_id
+    WHERE m.page_url = ?1
+    ORDER BY b.width DESC
+    LIMIT 1";
+
+#[derive(Debug, Deserialize)]
+struct BookmarksFile {
+    roots: HashMap<String, Node>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Node {
+    #[serde(rename = "type")]
+    kind: String,
+    name: String,
+    url: Option<String>,
+    #[serde(default)]
+    children: Vec<Node>,
+}
+
+pub fn load(root: &Path, favicons: &Path) -> Vec<Bookmark> {
+    profiles(root, "Bookmarks")
+        .iter()
+        .flat_map(|profile| {
+            load_profile(profile, favicons).unwrap_or_else(|err| {
+                warn!("Failed to read Chromium bookmarks in {profile:?}: {err}");
+                vec![]
+            })
+        })
+        .collect()
+}
+
+fn load_profile(profile: &Path, favicons: &Path) -> anyhow::Result<Vec<Bookmark>> {
+    let content = fs::read_to_string(profile.join("Bookmarks"))?;
+    let file: BookmarksFile = serde_json::from_str(&content)?;
+
+    // Keep the order displayed by the browser
+    let mut bookmarks = vec![];
+    for key in ["bookmark_bar", "other", "synced"].iter() {
+        if let Some(node) = file.roots.get(*key) {
+            collect(node, None, &mut bookmarks);
+        }
+    }
+
+    // Favicons are optional, a locked or corrupted database is not an error
+    let icons = profile.join("Favicons");
+    if icons.exists() {
+        if let Err(err) = load_favicons(&icons, favicons, &mut bookmarks) {
+            warn!("Failed to read Chromium favicons in {icons:?}: {err}");
+        }
+    }
+
+    Ok(bookmarks)
+}
+
+// The favicon database is locked while the browser is running
+fn load_favicons(icons: &Path, favicons: &Path, bookmarks: &mut [Bookmark]) -> anyhow::Result<()> {
+    let icons = Snapshot::new(icons)?;
+    let connection = icons.open()?;
+    let mut statement = connection.prepare(FAVICON_QUERY)?;
+    for bookmark in bookmarks {
+        let data: Option<Vec<u8>> = statement
+            .query_row([&bookmark.url], |row| row.get(0))
+            .optional()?;
+
+        bookmark.favicon = data.and_then(|data| store_favicon(favicons, &bookmark.url, &data));
+    }
+
+    Ok(())
+}
+
+fn collect(node: &Node, folder: Option<&str>, bookmarks: &mut Vec<Bookmark>) {
+    match (node.kind.as_str(), &node.url) {
+        ("url", Some(url)) => bookmarks.push(Bookmark {
+            title: node.name.clone(),
+            url: url.clone(),
+            folder: folder.map(str::to_string),
+            favicon: None,
+        }),
+        ("folder", _) => {
+            let path = match folder {
+                Some(folder) => format!("{folder}/{}", node.name),
+                None => node.name.clone(),
+            };
+
+            for child in &node.children {
+                collect(child, Some(&path), bookmarks);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bookmarks::chromium::load;
+    use crate::fixtures::fixture_dir;
+    use speculoos::prelude::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    // A profile with two bookmarks, returns the profiles root and the profile
+    fn chromium_profile(name: &str) -> (PathBuf, PathBuf) {
+        let root = fixture_dir(name);
+        let profile = root.join("Default");
+        fs::create_dir_all(&profile).unwrap();
+        fs::write(
+            profile.join("Bookmarks"),
+            r#"{
+                "checksum": "0",
+                "roots": {
+                    "bookmark_bar": {
+                        "type": "folder",
+                        "name": "Bookmarks bar",
+                        "children": [
+                            { "type": "url", "name": "crates.io", "url": "https://crates.io/" },
+                            {
+                                "type": "folder",
+                                "name": "Docs",
+                                "children": [
+                                    { "type": "url", "name": "docs.rs", "url": "https://docs.rs/" }
+                                ]
+                            }
+                        ]
+                    },
+                    "other": { "type": "folder", "name": "Other bookmarks", "children": [] }
+                },
+                "version": 1
+            }"#,
+        )
+        .unwrap();
+
+        (root, profile)
+    }
+
+    #[test]
+    fn should_load_chromium_profile() {
+        let (root, _) = chromium_profile("chromium");
+
+        let bookmarks = load(&root, &root.join("favicons"));
+
+        assert_that!(bookmarks).has_length(2);
+        assert_that!(bookmarks[0].url.as_str()).is_equal_to("https://crates.io/");
+        assert_that!(bookmarks[1].folder.as_deref()).is_equal_to(Some("Bookmarks bar/Docs"));
+    }
+
+    #[test]
+    fn should_load_bookmarks_without_favicons_from_corrupt_database() {
+        let (root, profile) = chromium_profile("chromium-corrupt-favicons");
+        fs::write(profile.join("Favicons"), "not a database").unwrap();
+
+        let bookmarks = load(&root, &root.join("favicons"));
+
+        assert_that!(bookmarks).has_length(2);
+        assert_that!(bookmarks.iter().all(|bookmark| bookmark.favicon.is_none())).is_true();
+    }
+}
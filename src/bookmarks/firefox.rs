@@ -0,0 +1,159 @@
+use crate::bookmarks::{profiles, store_favicon, Bookmark, Snapshot};
+use log::warn;
+use rusqlite::OptionalExtension;
+use std::path::Path;
+
+// Folder paths are built from the root children, e.g. `toolbar/Rust`
+const BOOKMARKS_QUERY: &str = "
+    WITH RECURSIVE folders(id, path) AS (
+        SELECT id, title FROM moz_bookmarks WHERE parent = 1 AND type = 2
+        UNION ALL
+        SELECT b.id, folders.path || '/' || b.title
+        FROM moz_bookmarks b JOIN folders ON b.parent = folders.id
+        WHERE b.type = 2
+    )
+    SELECT COALESCE(b.title, p.title, p.url), p.url, folders.path
+    FROM moz_bookmarks b
+    JOIN moz_places p ON p.id = b.fk
+    LEFT JOIN folders ON folders.id = b.parent
+    WHERE b.type = 1 AND p.url NOT LIKE 'place:%'
+    ORDER BY b.id";
+
+const FAVICON_QUERY: &str = "
+    SELECT i.data
+    FROM moz_pages_w_icons p
+    JOIN moz_icons_to_pages ip ON ip.page_id = p.id
+    JOIN moz_icons i ON i.id = ip.icon_id
+    WHERE p.page_url = ?1
+    ORDER BY i.width DESC
+    LIMIT 1";
+
+pub fn load(root: &Path, favicons: &Path) -> Vec<Bookmark> {
+    profiles(root, "places.sqlite")
+        .iter()
+        .flat_map(|profile| {
+            load_profile(profile, favicons).unwrap_or_else(|err| {
+                warn!("Failed to read Firefox bookmarks in {profile:?}: {err}");
+                vec![]
+            })
+        })
+        .collect()
+}
+
+fn load_profile(profile: &Path, favicons: &Path) -> anyhow::Result<Vec<Bookmark>> {
+    // Firefox keeps its databases locked while running
+    let places = Snapshot::new(&profile.join("places.sqlite"))?;
+    let connection = places.open()?;
+    let mut statement = connection.prepare(BOOKMARKS_QUERY)?;
+    let mut bookmarks = statement
+        .query_map([], |row| {
+            Ok(Bookmark {
+                title: row.get(0)?,
+                url: row.get(1)?,
+                folder: row.get(2)?,
+                favicon: None,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Favicons are optional, a missing or corrupted database is not an error
+    let icons = profile.join("favicons.sqlite");
+    if icons.exists() {
+        if let Err(err) = load_favicons(&icons, favicons, &mut bookmarks) {
+            warn!("Failed to read Firefox favicons in {icons:?}: {err}");
+        }
+    }
+
+    Ok(bookmarks)
+}
+
+fn load_favicons(icons: &Path, favicons: &Path, bookmarks: &mut [Bookmark]) -> anyhow::Result<()> {
+    let icons = Snapshot::new(icons)?;
+    let connection = icons.open()?;
+    let mut statement = connection.prepare(FAVICON_QUERY)?;
+    for bookmark in bookmarks {
+        let data: Option<Vec<u8>> = statement
+            .query_row([&bookmark.url], |row| row.get(0))
+            .optional()?;
+
+        bookmark.favicon = data.and_then(|data| store_favicon(favicons, &bookmark.url, &data));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bookmarks::firefox::load;
+    use crate::bookmarks::Bookmark;
+    use crate::fixtures::fixture_dir;
+    use rusqlite::Connection;
+    use speculoos::prelude::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    // A profile with a single bookmark, returns the profiles root and the profile
+    fn firefox_profile(name: &str) -> (PathBuf, PathBuf) {
+        let root = fixture_dir(name);
+        let profile = root.join("abcd.default-release");
+        fs::create_dir_all(&profile).unwrap();
+
+        let places = Connection::open(profile.join("places.sqlite")).unwrap();
+        places
+            .execute_batch(
+                "CREATE TABLE moz_places (id INTEGER PRIMARY KEY, url TEXT, title TEXT);
+                CREATE TABLE moz_bookmarks (id INTEGER PRIMARY KEY, type INTEGER, fk INTEGER, parent INTEGER, title TEXT);
+                INSERT INTO moz_places VALUES (1, 'https://doc.rust-lang.org/book/', 'The Rust Book');
+                INSERT INTO moz_places VALUES (2, 'place:sort=8', NULL);
+                INSERT INTO moz_bookmarks VALUES (1, 2, NULL, 0, 'root');
+                INSERT INTO moz_bookmarks VALUES (2, 2, NULL, 1, 'toolbar');
+                INSERT INTO moz_bookmarks VALUES (3, 2, NULL, 2, 'Rust');
+                INSERT INTO moz_bookmarks VALUES (4, 1, 1, 3, NULL);
+                INSERT INTO moz_bookmarks VALUES (5, 1, 2, 2, 'Most visited');",
+            )
+            .unwrap();
+
+        (root, profile)
+    }
+
+    #[test]
+    fn should_load_firefox_profile() {
+        let (root, profile) = firefox_profile("firefox");
+        let favicons = root.join("favicons");
+
+        let icons = Connection::open(profile.join("favicons.sqlite")).unwrap();
+        icons
+            .execute_batch(
+                "CREATE TABLE moz_icons (id INTEGER PRIMARY KEY, width INTEGER, data BLOB);
+                CREATE TABLE moz_pages_w_icons (id INTEGER PRIMARY KEY, page_url TEXT);
+                CREATE TABLE moz_icons_to_pages (page_id INTEGER, icon_id INTEGER);
+                INSERT INTO moz_icons VALUES (1, 16, X'89504E47');
+                INSERT INTO moz_pages_w_icons VALUES (1, 'https://doc.rust-lang.org/book/');
+                INSERT INTO moz_icons_to_pages VALUES (1, 1);",
+            )
+            .unwrap();
+
+        let bookmarks = load(&root, &favicons);
+
+        assert_that!(bookmarks).has_length(1);
+        let Bookmark {
+            title, url, folder, ..
+        } = &bookmarks[0];
+        assert_that!(title.as_str()).is_equal_to("The Rust Book");
+        assert_that!(url.as_str()).is_equal_to("https://doc.rust-lang.org/book/");
+        assert_that!(folder.as_deref()).is_equal_to(Some("toolbar/Rust"));
+        assert_that!(bookmarks[0].favicon.as_ref().map(|icon| icon.exists()))
+            .is_equal_to(Some(true));
+    }
+
+    #[test]
+    fn should_load_bookmarks_without_favicons_from_corrupt_database() {
+        let (root, profile) = firefox_profile("firefox-corrupt-favicons");
+        fs::write(profile.join("favicons.sqlite"), "not a database").unwrap();
+
+        let bookmarks = load(&root, &root.join("favicons"));
+
+        assert_that!(bookmarks).has_length(1);
+        assert_that!(bookmarks[0].favicon).is_none();
+    }
+}
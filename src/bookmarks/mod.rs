@@ -0,0 +1,202 @@
+use rusqlite::Connection;
+use std::collections::hash_map::DefaultHasher;
+use std::ffi::OsString;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+mod chromium;
+mod firefox;
+
+// Chromium based browsers, relative to `$XDG_CONFIG_HOME`
+const CHROMIUM_DIRS: &[&str] = &[
+    "chromium",
+    "google-chrome",
+    "BraveSoftware/Brave-Browser",
+    "vivaldi",
+    "microsoft-edge",
+];
+
+// Firefox profiles, relative to `$HOME`, for the native, flatpak and snap packages
+const FIREFOX_DIRS: &[&str] = &[
+    ".mozilla/firefox",
+    ".var/app/org.mozilla.firefox/.mozilla/firefox",
+    "snap/firefox/common/.mozilla/firefox",
+];
+
+static SNAPSHOTS: AtomicUsize = AtomicUsize::new(0);
+
+// Recent changes might only be in the write-ahead or rollback journal
+const JOURNALS: &[&str] = &["-wal", "-journal"];
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Bookmark {
+    pub title: String,
+    pub url: String,
+    // Slash separated folder path, e.g. `Bookmarks bar/Rust`
+    pub folder: Option<String>,
+    // Favicon as stored by the browser, converted to png by the icon pipeline
+    pub favicon: Option<PathBuf>,
+}
+
+impl Bookmark {
+    // Best fuzzy score of the title, url and folder
+    pub fn score(&self, query: &str) -> Option<i64> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Some(0);
+        }
+
+        [Some(&self.title), Some(&self.url), self.folder.as_ref()]
+            .iter()
+            .flatten()
+            .filter_map(|field| fuzzy_score(query, field))
+            .max()
+    }
+}
+
+// Bookmarks from every Firefox and Chromium profile found
+pub fn bookmarks() -> Vec<Bookmark> {
+    let favicons = match dirs::cache_dir() {
+        Some(cache) => cache.join("onagre").join("favicons"),
+        None => return vec![],
+    };
+
+    let firefox = dirs::home_dir()
+        .into_iter()
+        .flat_map(|home| FIREFOX_DIRS.iter().map(move |dir| home.join(dir)));
+
+    let chromium = dirs::config_dir()
+        .into_iter()
+        .flat_map(|config| CHROMIUM_DIRS.iter().map(move |dir| config.join(dir)));
+
+    let mut bookmarks: Vec<Bookmark> = firefox
+        .flat_map(|root| firefox::load(&root, &favicons))
+        .chain(chromium.flat_map(|root| chromium::load(&root, &favicons)))
+        .collect();
+
+    // The same page is often bookmarked in several browsers
+    let mut seen = std::collections::HashSet::new();
+    bookmarks.retain(|bookmark| seen.insert(bookmark.url.clone()));
+    bookmarks
+}
+
+// Browsers lock their databases while running, we read a temporary copy instead
+struct Snapshot {
+    path: PathBuf,
+}
+
+impl Snapshot {
+    fn new(database: &Path) -> anyhow::Result<Snapshot> {
+        let id = SNAPSHOTS.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!(
+            "onagre-{}-{id}-{}",
+            std::process::id(),
+            database.file_name().unwrap_or_default().to_string_lossy()
+        ));
+
+        fs::copy(database, &path)?;
+        for journal in JOURNALS {
+            let source = with_suffix(database, journal);
+            if source.exists() {
+                fs::copy(source, with_suffix(&path, journal))?;
+            }
+        }
+
+        Ok(Snapshot { path })
+    }
+
+    fn open(&self) -> rusqlite::Result<Connection> {
+        Connection::open(&self.path)
+    }
+}
+
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+        for suffix in JOURNALS.iter().chain(["-shm"].iter()) {
+            let _ = fs::remove_file(with_suffix(&self.path, suffix));
+        }
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = OsString::from(path);
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+// Profile directories under `root` containing `file`
+fn profiles(root: &Path, file: &str) -> Vec<PathBuf> {
+    let mut profiles: Vec<PathBuf> = fs::read_dir(root)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.join(file).is_file())
+        .collect();
+
+    profiles.sort();
+    profiles
+}
+
+// Write the favicon of `url` to the cache, it may be an ico, png or svg image
+fn store_favicon(favicons: &Path, url: &str, data: &[u8]) -> Option<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+    let path = favicons.join(format!("{:x}.favicon", hasher.finish()));
+
+    // Keep the modification time, converted icons are reused until it changes
+    if fs::read(&path).ok().as_deref() != Some(data) {
+        fs::create_dir_all(favicons).ok()?;
+        fs::write(&path, data).ok()?;
+    }
+
+    Some(path)
+}
+
+// Subsequence match, consecutive characters and word starts score higher
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+
+    for query_char in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = position + text[position..].iter().position(|c| *c == query_char)?;
+
+        score += 1;
+        if found > 0 && previous == Some(found - 1) {
+            score += 5;
+        }
+
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+
+        previous = Some(found);
+        position = found + 1;
+    }
+
+    // Prefer shorter texts when the query matches equally
+    Some(score * 100 - text.len() as i64)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bookmarks::fuzzy_score;
+    use speculoos::prelude::*;
+
+    #[test]
+    fn should_fuzzy_match() {
+        let exact = fuzzy_score("rust", "The Rust Book");
+        let scattered = fuzzy_score("rust", "React useState");
+        let missing = fuzzy_score("rust", "Python docs");
+
+        assert_that!(exact).is_some();
+        assert_that!(scattered).is_some();
+        assert_that!(exact.unwrap()).is_greater_than(scattered.unwrap());
+        assert_that!(missing).is_none();
+    }
+}
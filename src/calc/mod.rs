@@ -0,0 +1,162 @@
+use crate::calc::parser::{Parser, Target};
+use crate::calc::units::Quantity;
+use thiserror::Error;
+
+mod parser;
+mod units;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum CalcError {
+    #[error("Unexpected character '{0}'")]
+    UnexpectedChar(char),
+    #[error("Invalid number '{0}'")]
+    InvalidNumber(String),
+    #[error("Unexpected '{0}'")]
+    UnexpectedToken(String),
+    #[error("Incomplete expression")]
+    UnexpectedEnd,
+    #[error("Unknown function or constant '{0}'")]
+    UnknownIdentifier(String),
+    #[error("Unknown unit '{0}'")]
+    UnknownUnit(String),
+    #[error("'{0}' expects {1} argument(s)")]
+    Arity(String, usize),
+    #[error("Cannot convert {0} to {1}")]
+    IncompatibleUnits(&'static str, &'static str),
+    #[error("Unsupported operation on units")]
+    UnitOperation,
+    #[error("Only integers can be converted to base {0}")]
+    NotAnInteger(u32),
+    #[error("Division by zero")]
+    DivisionByZero,
+    #[error("Undefined result")]
+    Undefined,
+}
+
+// Evaluate an expression such as `2 * (3 + 4)`, `sqrt(2)`, `255 to hex` or `10 km to mi`
+pub fn evaluate(expression: &str) -> Result<String, CalcError> {
+    let tokens = parser::tokenize(expression)?;
+    let (quantity, target) = Parser::new(tokens).parse()?;
+
+    if !quantity.value.is_finite() {
+        return Err(CalcError::Undefined);
+    }
+
+    match target {
+        None => Ok(format_quantity(quantity)),
+        Some(Target::Unit(unit)) => Ok(format_quantity(quantity.convert(unit)?)),
+        Some(Target::Base(base)) => format_base(quantity.number()?, base),
+    }
+}
+
+// Input typed without the `=` prefix is treated as an expression
+// when it evaluates and is more than a single number
+pub fn is_expression(input: &str) -> bool {
+    input.chars().any(|c| c.is_ascii_digit())
+        && matches!(parser::tokenize(input), Ok(tokens) if tokens.len() > 1)
+        && evaluate(input).is_ok()
+}
+
+fn format_quantity(quantity: Quantity) -> String {
+    let value = format_number(quantity.value);
+    match quantity.unit {
+        Some(unit) => format!("{value} {}", unit.symbol),
+        None => value,
+    }
+}
+
+fn format_number(value: f64) -> String {
+    let abs = value.abs();
+
+    if value.fract() == 0.0 && abs < 1e15 {
+        return format!("{}", value as i64);
+    }
+
+    if !(1e-9..1e15).contains(&abs) {
+        return format!("{value:e}");
+    }
+
+    format!("{value:.10}")
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+fn format_base(value: f64, base: u32) -> Result<String, CalcError> {
+    if value.fract() != 0.0 || value.abs() >= u64::MAX as f64 {
+        return Err(CalcError::NotAnInteger(base));
+    }
+
+    let sign = if value < 0.0 { "-" } else { "" };
+    let value = value.abs() as u64;
+
+    Ok(match base {
+        2 => format!("{sign}0b{value:b}"),
+        8 => format!("{sign}0o{value:o}"),
+        16 => format!("{sign}0x{value:x}"),
+        _ => format!("{sign}{value}"),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::calc::{evaluate, is_expression, CalcError};
+    use speculoos::prelude::*;
+
+    #[test]
+    fn should_respect_operator_precedence() {
+        assert_that!(evaluate("2 + 3 * 4")).is_ok_containing("14".to_string());
+        assert_that!(evaluate("(2 + 3) * 4")).is_ok_containing("20".to_string());
+        assert_that!(evaluate("-2^2")).is_ok_containing("-4".to_string());
+        assert_that!(evaluate("2^3^2")).is_ok_containing("512".to_string());
+    }
+
+    #[test]
+    fn should_round_floating_point_results() {
+        assert_that!(evaluate("0.1 + 0.2")).is_ok_containing("0.3".to_string());
+        assert_that!(evaluate("1 / 3")).is_ok_containing("0.3333333333".to_string());
+    }
+
+    #[test]
+    fn should_evaluate_functions_and_constants() {
+        assert_that!(evaluate("sqrt(16) + abs(-2)")).is_ok_containing("6".to_string());
+        assert_that!(evaluate("max(1, 7, 3)")).is_ok_containing("7".to_string());
+        assert_that!(evaluate("cos(pi)")).is_ok_containing("-1".to_string());
+        assert_that!(evaluate("5!")).is_ok_containing("120".to_string());
+    }
+
+    #[test]
+    fn should_convert_between_bases() {
+        assert_that!(evaluate("255 to hex")).is_ok_containing("0xff".to_string());
+        assert_that!(evaluate("0xff + 1")).is_ok_containing("256".to_string());
+        assert_that!(evaluate("10 in bin")).is_ok_containing("0b1010".to_string());
+        assert_that!(evaluate("1.5 to hex")).is_err_containing(CalcError::NotAnInteger(16));
+    }
+
+    #[test]
+    fn should_convert_units() {
+        assert_that!(evaluate("10 km to mi")).is_ok_containing("6.2137119224 mi".to_string());
+        assert_that!(evaluate("1 km + 500 m")).is_ok_containing("1.5 km".to_string());
+        assert_that!(evaluate("100 °C to F")).is_ok_containing("212 °F".to_string());
+        assert_that!(evaluate("1 GiB to MiB")).is_ok_containing("1024 MiB".to_string());
+        assert_that!(evaluate("1 km to kg"))
+            .is_err_containing(CalcError::IncompatibleUnits("km", "kg"));
+    }
+
+    #[test]
+    fn should_report_errors() {
+        assert_that!(evaluate("1 / 0")).is_err_containing(CalcError::DivisionByZero);
+        assert_that!(evaluate("(1 + 2")).is_err_containing(CalcError::UnexpectedEnd);
+        assert_that!(evaluate("foo(2)"))
+            .is_err_containing(CalcError::UnknownIdentifier("foo".to_string()));
+    }
+
+    #[test]
+    fn should_detect_expressions() {
+        assert_that!(is_expression("12 * 7")).is_true();
+        assert_that!(is_expression("2 km to m")).is_true();
+        assert_that!(is_expression("42")).is_false();
+        assert_that!(is_expression("0ad")).is_false();
+        assert_that!(is_expression("firefox")).is_false();
+    }
+}
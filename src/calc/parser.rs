@@ -0,0 +1,456 @@
+use crate::calc::units::{self, Quantity, Unit};
+use crate::calc::CalcError;
+use std::fmt;
+
+// Keywords introducing a conversion, e.g. `255 to hex` or `10 km in mi`
+const CONVERSION_KEYWORDS: [&str; 3] = ["to", "in", "as"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Number(f64),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Caret,
+    Bang,
+    Comma,
+    LParen,
+    RParen,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(value) => write!(f, "{value}"),
+            Token::Ident(name) => write!(f, "{name}"),
+            Token::Plus => write!(f, "+"),
+            Token::Minus => write!(f, "-"),
+            Token::Star => write!(f, "*"),
+            Token::Slash => write!(f, "/"),
+            Token::Percent => write!(f, "%"),
+            Token::Caret => write!(f, "^"),
+            Token::Bang => write!(f, "!"),
+            Token::Comma => write!(f, ","),
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Target {
+    Base(u32),
+    Unit(&'static Unit),
+}
+
+pub fn tokenize(input: &str) -> Result<Vec<Token>, CalcError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = vec![];
+    let mut position = 0;
+
+    while position < chars.len() {
+        let c = chars[position];
+
+        if c.is_whitespace() {
+            position += 1;
+            continue;
+        }
+
+        if c.is_ascii_digit() || c == '.' {
+            let (token, end) = number(&chars, position)?;
+            tokens.push(token);
+            position = end;
+            continue;
+        }
+
+        if c.is_alphabetic() || c == '_' || c == '°' {
+            let end = scan(&chars, position, |c| {
+                c.is_alphanumeric() || c == '_' || c == '°'
+            });
+
+            tokens.push(Token::Ident(chars[position..end].iter().collect()));
+            position = end;
+            continue;
+        }
+
+        let token = match c {
+            '+' => Token::Plus,
+            '-' | '−' => Token::Minus,
+            // `**` is an alias for `^`
+            '*' if chars.get(position + 1) == Some(&'*') => {
+                position += 1;
+                Token::Caret
+            }
+            '*' | '×' => Token::Star,
+            '/' | '÷' => Token::Slash,
+            '%' => Token::Percent,
+            '^' => Token::Caret,
+            '!' => Token::Bang,
+            ',' => Token::Comma,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            other => return Err(CalcError::UnexpectedChar(other)),
+        };
+
+        tokens.push(token);
+        position += 1;
+    }
+
+    Ok(tokens)
+}
+
+// Parse a number literal starting at `start`, returns the token and the position right after it
+fn number(chars: &[char], start: usize) -> Result<(Token, usize), CalcError> {
+    let radix = match (chars[start], chars.get(start + 1)) {
+        ('0', Some('x' | 'X')) => Some(16),
+        ('0', Some('b' | 'B')) => Some(2),
+        ('0', Some('o' | 'O')) => Some(8),
+        _ => None,
+    };
+
+    if let Some(radix) = radix {
+        let digits_start = start + 2;
+        let end = scan(chars, digits_start, |c| c.is_digit(radix) || c == '_');
+        let digits: String = chars[digits_start..end]
+            .iter()
+            .filter(|c| **c != '_')
+            .collect();
+
+        let literal: String = chars[start..end].iter().collect();
+        let value =
+            u64::from_str_radix(&digits, radix).map_err(|_| CalcError::InvalidNumber(literal))?;
+
+        return Ok((Token::Number(value as f64), end));
+    }
+
+    let mut end = scan(chars, start, |c| c.is_ascii_digit() || c == '_' || c == '.');
+
+    // Only consume the exponent when digits follow, `2e` is not a number
+    if let Some('e' | 'E') = chars.get(end) {
+        let digits = match chars.get(end + 1) {
+            Some('+' | '-') => end + 2,
+            _ => end + 1,
+        };
+
+        if matches!(chars.get(digits), Some(c) if c.is_ascii_digit()) {
+            end = scan(chars, digits, |c| c.is_ascii_digit());
+        }
+    }
+
+    let literal: String = chars[start..end].iter().collect();
+    let value = literal
+        .replace('_', "")
+        .parse::<f64>()
+        .map_err(|_| CalcError::InvalidNumber(literal))?;
+
+    Ok((Token::Number(value), end))
+}
+
+fn scan(chars: &[char], start: usize, accept: impl Fn(char) -> bool) -> usize {
+    chars[start..]
+        .iter()
+        .position(|c| !accept(*c))
+        .map_or(chars.len(), |len| start + len)
+}
+
+// A recursive descent parser evaluating the expression on the fly:
+//
+// expression := sum (("to" | "in" | "as") target)?
+// sum        := product (("+" | "-") product)*
+// product    := unary (("*" | "/" | "%") unary)*
+// unary      := ("-" | "+") unary | power
+// power      := postfix ("^" unary)?
+// postfix    := primary "!"* unit?
+// primary    := number | constant | function "(" arguments ")" | "(" sum ")"
+pub struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    pub fn new(tokens: Vec<Token>) -> Self {
+        Parser {
+            tokens,
+            position: 0,
+        }
+    }
+
+    pub fn parse(mut self) -> Result<(Quantity, Option<Target>), CalcError> {
+        let quantity = self.sum()?;
+
+        let target = match self.peek() {
+            Some(Token::Ident(keyword)) if CONVERSION_KEYWORDS.contains(&keyword.as_str()) => {
+                self.advance();
+                Some(self.target()?)
+            }
+            _ => None,
+        };
+
+        match self.advance() {
+            None => Ok((quantity, target)),
+            Some(token) => Err(CalcError::UnexpectedToken(token.to_string())),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        if token.is_some() {
+            self.position += 1;
+        }
+
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), CalcError> {
+        match self.advance() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(CalcError::UnexpectedToken(token.to_string())),
+            None => Err(CalcError::UnexpectedEnd),
+        }
+    }
+
+    fn target(&mut self) -> Result<Target, CalcError> {
+        match self.advance() {
+            Some(Token::Ident(name)) => match name.as_str() {
+                "hex" | "hexadecimal" => Ok(Target::Base(16)),
+                "bin" | "binary" => Ok(Target::Base(2)),
+                "oct" | "octal" => Ok(Target::Base(8)),
+                "dec" | "decimal" => Ok(Target::Base(10)),
+                _ => units::lookup(&name)
+                    .map(Target::Unit)
+                    .ok_or_else(|| CalcError::UnknownUnit(name.clone())),
+            },
+            Some(token) => Err(CalcError::UnexpectedToken(token.to_string())),
+            None => Err(CalcError::UnexpectedEnd),
+        }
+    }
+
+    fn sum(&mut self) -> Result<Quantity, CalcError> {
+        let mut quantity = self.product()?;
+
+        loop {
+            quantity = match self.peek() {
+                Some(Token::Plus) => {
+                    self.advance();
+                    quantity.add(self.product()?)?
+                }
+                Some(Token::Minus) => {
+                    self.advance();
+                    quantity.sub(self.product()?)?
+                }
+                _ => return Ok(quantity),
+            };
+        }
+    }
+
+    fn product(&mut self) -> Result<Quantity, CalcError> {
+        let mut quantity = self.unary()?;
+
+        loop {
+            quantity = match self.peek() {
+                Some(Token::Star) => {
+                    self.advance();
+                    quantity.mul(self.unary()?)?
+                }
+                Some(Token::Slash) => {
+                    self.advance();
+                    quantity.div(self.unary()?)?
+                }
+                Some(Token::Percent) => {
+                    self.advance();
+                    quantity.rem(self.unary()?)?
+                }
+                _ => return Ok(quantity),
+            };
+        }
+    }
+
+    fn unary(&mut self) -> Result<Quantity, CalcError> {
+        match self.peek() {
+            Some(Token::Minus) => {
+                self.advance();
+                Ok(self.unary()?.neg())
+            }
+            Some(Token::Plus) => {
+                self.advance();
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<Quantity, CalcError> {
+        let base = self.postfix()?;
+
+        if self.peek() == Some(&Token::Caret) {
+            self.advance();
+            let exponent = self.unary()?.number()?;
+            return Ok(Quantity::scalar(base.number()?.powf(exponent)));
+        }
+
+        Ok(base)
+    }
+
+    fn postfix(&mut self) -> Result<Quantity, CalcError> {
+        let mut quantity = self.primary()?;
+
+        while self.peek() == Some(&Token::Bang) {
+            self.advance();
+            quantity = Quantity::scalar(factorial(quantity.number()?)?);
+        }
+
+        // A unit directly follows its value, `min(` is a function call not minutes
+        let unit = match (self.peek(), self.tokens.get(self.position + 1)) {
+            (Some(Token::Ident(name)), next) if next != Some(&Token::LParen) => units::lookup(name),
+            _ => None,
+        };
+
+        if let Some(unit) = unit {
+            self.advance();
+            quantity = quantity.with_unit(unit)?;
+        }
+
+        Ok(quantity)
+    }
+
+    fn primary(&mut self) -> Result<Quantity, CalcError> {
+        match self.advance() {
+            Some(Token::Number(value)) => Ok(Quantity::scalar(value)),
+            Some(Token::LParen) => {
+                let quantity = self.sum()?;
+                self.expect(Token::RParen)?;
+                Ok(quantity)
+            }
+            Some(Token::Ident(name)) if self.peek() == Some(&Token::LParen) => {
+                let arguments = self.arguments()?;
+                function(&name, &arguments).map(Quantity::scalar)
+            }
+            Some(Token::Ident(name)) => constant(&name)
+                .map(Quantity::scalar)
+                .ok_or(CalcError::UnknownIdentifier(name)),
+            Some(token) => Err(CalcError::UnexpectedToken(token.to_string())),
+            None => Err(CalcError::UnexpectedEnd),
+        }
+    }
+
+    fn arguments(&mut self) -> Result<Vec<f64>, CalcError> {
+        self.expect(Token::LParen)?;
+        let mut arguments = vec![];
+
+        if self.peek() != Some(&Token::RParen) {
+            loop {
+                arguments.push(self.sum()?.number()?);
+                if self.peek() == Some(&Token::Comma) {
+                    self.advance();
+                } else {
+                    break;
+                }
+            }
+        }
+
+        self.expect(Token::RParen)?;
+        Ok(arguments)
+    }
+}
+
+fn constant(name: &str) -> Option<f64> {
+    match name {
+        "pi" | "π" => Some(std::f64::consts::PI),
+        "tau" | "τ" => Some(std::f64::consts::TAU),
+        "e" => Some(std::f64::consts::E),
+        "phi" | "φ" => Some(1.618_033_988_749_895),
+        _ => None,
+    }
+}
+
+fn function(name: &str, arguments: &[f64]) -> Result<f64, CalcError> {
+    let unary: Option<fn(f64) -> f64> = match name {
+        "sqrt" => Some(f64::sqrt),
+        "cbrt" => Some(f64::cbrt),
+        "abs" => Some(f64::abs),
+        "ln" => Some(f64::ln),
+        "log" | "log10" => Some(f64::log10),
+        "log2" => Some(f64::log2),
+        "exp" => Some(f64::exp),
+        "sin" => Some(f64::sin),
+        "cos" => Some(f64::cos),
+        "tan" => Some(f64::tan),
+        "asin" => Some(f64::asin),
+        "acos" => Some(f64::acos),
+        "atan" => Some(f64::atan),
+        "sinh" => Some(f64::sinh),
+        "cosh" => Some(f64::cosh),
+        "tanh" => Some(f64::tanh),
+        "floor" => Some(f64::floor),
+        "ceil" => Some(f64::ceil),
+        "round" => Some(f64::round),
+        "trunc" => Some(f64::trunc),
+        _ => None,
+    };
+
+    if let Some(function) = unary {
+        return match arguments {
+            [x] => Ok(function(*x)),
+            _ => Err(CalcError::Arity(name.to_string(), 1)),
+        };
+    }
+
+    match (name, arguments) {
+        ("min", [_, ..]) => Ok(arguments.iter().copied().fold(f64::INFINITY, f64::min)),
+        ("max", [_, ..]) => Ok(arguments.iter().copied().fold(f64::NEG_INFINITY, f64::max)),
+        ("pow", [x, y]) => Ok(x.powf(*y)),
+        ("min" | "max", _) => Err(CalcError::Arity(name.to_string(), 1)),
+        ("pow", _) => Err(CalcError::Arity(name.to_string(), 2)),
+        _ => Err(CalcError::UnknownIdentifier(name.to_string())),
+    }
+}
+
+fn factorial(n: f64) -> Result<f64, CalcError> {
+    if n < 0.0 || n.fract() != 0.0 {
+        return Err(CalcError::Undefined);
+    }
+
+    // Anything above overflows an f64
+    if n > 170.0 {
+        return Ok(f64::INFINITY);
+    }
+
+    Ok((1..=n as u64).map(|i| i as f64).product())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::calc::parser::{tokenize, Token};
+    use speculoos::prelude::*;
+
+    #[test]
+    fn should_tokenize_prefixed_numbers() {
+        let tokens = tokenize("0xff + 0b1010 * 1_000").unwrap();
+
+        assert_that!(tokens).is_equal_to(vec![
+            Token::Number(255.0),
+            Token::Plus,
+            Token::Number(10.0),
+            Token::Star,
+            Token::Number(1000.0),
+        ]);
+    }
+
+    #[test]
+    fn should_tokenize_exponent_only_when_followed_by_digits() {
+        let tokens = tokenize("1.5e-3 2e").unwrap();
+
+        assert_that!(tokens).is_equal_to(vec![
+            Token::Number(0.0015),
+            Token::Number(2.0),
+            Token::Ident("e".to_string()),
+        ]);
+    }
+}
@@ -0,0 +1,323 @@
+use crate::calc::CalcError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dimension {
+    Length,
+    Mass,
+    Time,
+    Temperature,
+    Data,
+    Volume,
+    Speed,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Unit {
+    pub symbol: &'static str,
+    aliases: &'static [&'static str],
+    dimension: Dimension,
+    // Value of one unit expressed in the base unit of its dimension
+    factor: f64,
+    // Only used for temperatures, their zero does not match the base unit zero
+    offset: f64,
+}
+
+const fn unit(
+    symbol: &'static str,
+    aliases: &'static [&'static str],
+    dimension: Dimension,
+    factor: f64,
+) -> Unit {
+    Unit {
+        symbol,
+        aliases,
+        dimension,
+        factor,
+        offset: 0.0,
+    }
+}
+
+static UNITS: &[Unit] = &[
+    // Length, base unit is the meter
+    unit(
+        "mm",
+        &["millimeter", "millimeters", "millimetre", "millimetres"],
+        Dimension::Length,
+        0.001,
+    ),
+    unit(
+        "cm",
+        &["centimeter", "centimeters", "centimetre", "centimetres"],
+        Dimension::Length,
+        0.01,
+    ),
+    unit(
+        "m",
+        &["meter", "meters", "metre", "metres"],
+        Dimension::Length,
+        1.0,
+    ),
+    unit(
+        "km",
+        &["kilometer", "kilometers", "kilometre", "kilometres"],
+        Dimension::Length,
+        1000.0,
+    ),
+    unit("inch", &["inches"], Dimension::Length, 0.0254),
+    unit("ft", &["foot", "feet"], Dimension::Length, 0.3048),
+    unit("yd", &["yard", "yards"], Dimension::Length, 0.9144),
+    unit("mi", &["mile", "miles"], Dimension::Length, 1609.344),
+    unit(
+        "nmi",
+        &["nautical_mile", "nautical_miles"],
+        Dimension::Length,
+        1852.0,
+    ),
+    // Mass, base unit is the gram
+    unit("mg", &["milligram", "milligrams"], Dimension::Mass, 0.001),
+    unit("g", &["gram", "grams"], Dimension::Mass, 1.0),
+    unit("kg", &["kilogram", "kilograms"], Dimension::Mass, 1000.0),
+    unit("t", &["tonne", "tonnes"], Dimension::Mass, 1_000_000.0),
+    unit("oz", &["ounce", "ounces"], Dimension::Mass, 28.349_523_125),
+    unit(
+        "lb",
+        &["lbs", "pound", "pounds"],
+        Dimension::Mass,
+        453.592_37,
+    ),
+    // Time, base unit is the second
+    unit(
+        "ms",
+        &["millisecond", "milliseconds"],
+        Dimension::Time,
+        0.001,
+    ),
+    unit("s", &["sec", "second", "seconds"], Dimension::Time, 1.0),
+    unit("min", &["minute", "minutes"], Dimension::Time, 60.0),
+    unit("h", &["hour", "hours"], Dimension::Time, 3600.0),
+    unit("day", &["days"], Dimension::Time, 86_400.0),
+    unit("week", &["weeks"], Dimension::Time, 604_800.0),
+    unit("year", &["years"], Dimension::Time, 31_557_600.0),
+    // Temperature, base unit is the kelvin
+    unit("K", &["kelvin"], Dimension::Temperature, 1.0),
+    Unit {
+        symbol: "°C",
+        aliases: &["C", "celsius"],
+        dimension: Dimension::Temperature,
+        factor: 1.0,
+        offset: 273.15,
+    },
+    Unit {
+        symbol: "°F",
+        aliases: &["F", "fahrenheit"],
+        dimension: Dimension::Temperature,
+        factor: 5.0 / 9.0,
+        offset: 273.15 - 32.0 * 5.0 / 9.0,
+    },
+    // Data, base unit is the byte
+    unit("bit", &["bits"], Dimension::Data, 0.125),
+    unit("B", &["byte", "bytes"], Dimension::Data, 1.0),
+    unit("kB", &["KB"], Dimension::Data, 1e3),
+    unit("MB", &[], Dimension::Data, 1e6),
+    unit("GB", &[], Dimension::Data, 1e9),
+    unit("TB", &[], Dimension::Data, 1e12),
+    unit("KiB", &[], Dimension::Data, 1024.0),
+    unit("MiB", &[], Dimension::Data, 1_048_576.0),
+    unit("GiB", &[], Dimension::Data, 1_073_741_824.0),
+    unit("TiB", &[], Dimension::Data, 1_099_511_627_776.0),
+    // Volume, base unit is the liter
+    unit(
+        "ml",
+        &[
+            "mL",
+            "milliliter",
+            "milliliters",
+            "millilitre",
+            "millilitres",
+        ],
+        Dimension::Volume,
+        0.001,
+    ),
+    unit(
+        "cl",
+        &[
+            "cL",
+            "centiliter",
+            "centiliters",
+            "centilitre",
+            "centilitres",
+        ],
+        Dimension::Volume,
+        0.01,
+    ),
+    unit(
+        "l",
+        &["L", "liter", "liters", "litre", "litres"],
+        Dimension::Volume,
+        1.0,
+    ),
+    unit(
+        "gal",
+        &["gallon", "gallons"],
+        Dimension::Volume,
+        3.785_411_784,
+    ),
+    // Speed, base unit is the meter per second
+    unit("mps", &[], Dimension::Speed, 1.0),
+    unit("kmh", &["kph"], Dimension::Speed, 1.0 / 3.6),
+    unit("mph", &[], Dimension::Speed, 0.447_04),
+    unit("kn", &["knot", "knots"], Dimension::Speed, 1852.0 / 3600.0),
+];
+
+pub fn lookup(name: &str) -> Option<&'static Unit> {
+    UNITS
+        .iter()
+        .find(|unit| unit.symbol == name || unit.aliases.contains(&name))
+}
+
+impl Unit {
+    fn base_value(&self, value: f64) -> f64 {
+        value * self.factor + self.offset
+    }
+
+    fn unit_value(&self, value: f64) -> f64 {
+        (value - self.offset) / self.factor
+    }
+
+    pub fn convert(&self, value: f64, to: &Unit) -> Result<f64, CalcError> {
+        if self.dimension != to.dimension {
+            return Err(CalcError::IncompatibleUnits(self.symbol, to.symbol));
+        }
+
+        Ok(to.unit_value(self.base_value(value)))
+    }
+}
+
+// A number, optionally expressed in a unit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quantity {
+    pub value: f64,
+    pub unit: Option<&'static Unit>,
+}
+
+impl Quantity {
+    pub fn scalar(value: f64) -> Self {
+        Quantity { value, unit: None }
+    }
+
+    pub fn with_unit(self, unit: &'static Unit) -> Result<Self, CalcError> {
+        match self.unit {
+            None => Ok(Quantity {
+                value: self.value,
+                unit: Some(unit),
+            }),
+            Some(_) => Err(CalcError::UnitOperation),
+        }
+    }
+
+    // Functions, powers and base conversions are only defined for plain numbers
+    pub fn number(self) -> Result<f64, CalcError> {
+        match self.unit {
+            None => Ok(self.value),
+            Some(_) => Err(CalcError::UnitOperation),
+        }
+    }
+
+    pub fn convert(self, to: &'static Unit) -> Result<Self, CalcError> {
+        let value = match self.unit {
+            Some(unit) => unit.convert(self.value, to)?,
+            None => self.value,
+        };
+
+        Ok(Quantity {
+            value,
+            unit: Some(to),
+        })
+    }
+
+    pub fn neg(self) -> Self {
+        Quantity {
+            value: -self.value,
+            unit: self.unit,
+        }
+    }
+
+    pub fn add(self, rhs: Self) -> Result<Self, CalcError> {
+        match (self.unit, rhs.unit) {
+            (None, None) => Ok(Quantity::scalar(self.value + rhs.value)),
+            (Some(unit), Some(rhs_unit)) => Ok(Quantity {
+                value: self.value + rhs_unit.convert(rhs.value, unit)?,
+                unit: Some(unit),
+            }),
+            _ => Err(CalcError::UnitOperation),
+        }
+    }
+
+    pub fn sub(self, rhs: Self) -> Result<Self, CalcError> {
+        self.add(rhs.neg())
+    }
+
+    pub fn mul(self, rhs: Self) -> Result<Self, CalcError> {
+        match (self.unit, rhs.unit) {
+            (Some(_), Some(_)) => Err(CalcError::UnitOperation),
+            (unit, rhs_unit) => Ok(Quantity {
+                value: self.value * rhs.value,
+                unit: unit.or(rhs_unit),
+            }),
+        }
+    }
+
+    pub fn div(self, rhs: Self) -> Result<Self, CalcError> {
+        // Dividing two quantities of the same dimension gives a ratio
+        let divisor = match (self.unit, rhs.unit) {
+            (_, None) => rhs.value,
+            (Some(unit), Some(rhs_unit)) => rhs_unit.convert(rhs.value, unit)?,
+            (None, Some(_)) => return Err(CalcError::UnitOperation),
+        };
+
+        if divisor == 0.0 {
+            return Err(CalcError::DivisionByZero);
+        }
+
+        Ok(Quantity {
+            value: self.value / divisor,
+            unit: if rhs.unit.is_some() { None } else { self.unit },
+        })
+    }
+
+    pub fn rem(self, rhs: Self) -> Result<Self, CalcError> {
+        let divisor = rhs.number()?;
+        if divisor == 0.0 {
+            return Err(CalcError::DivisionByZero);
+        }
+
+        Ok(Quantity::scalar(self.number()? % divisor))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::calc::units::lookup;
+    use crate::calc::CalcError;
+    use speculoos::prelude::*;
+
+    #[test]
+    fn should_convert_temperatures() {
+        let celsius = lookup("celsius").unwrap();
+        let fahrenheit = lookup("°F").unwrap();
+
+        let converted = celsius.convert(100.0, fahrenheit).unwrap();
+
+        assert_that!(converted).is_close_to(212.0, 1e-9);
+    }
+
+    #[test]
+    fn should_not_convert_across_dimensions() {
+        let km = lookup("km").unwrap();
+        let kg = lookup("kg").unwrap();
+
+        let converted = km.convert(1.0, kg);
+
+        assert_that!(converted).is_err_containing(CalcError::IncompatibleUnits("km", "kg"));
+    }
+}
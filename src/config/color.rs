@@ -135,6 +135,21 @@ fn to_lower_gex_with_leading_zero(value: u32) -> String {
     }
 }
 
+impl OnagreColor {
+    // Linear interpolation towards `other`, used by transitions
+    pub(crate) fn mix(self, other: OnagreColor, t: f32) -> OnagreColor {
+        let mix = |from: f32, to: f32| from + (to - from) * t;
+        OnagreColor {
+            color: Color {
+                r: mix(self.color.r, other.color.r),
+                g: mix(self.color.g, other.color.g),
+                b: mix(self.color.b, other.color.b),
+                a: mix(self.color.a, other.color.a),
+            },
+        }
+    }
+}
+
 impl From<OnagreColor> for Color {
     fn from(color: OnagreColor) -> Self {
         Color {
@@ -16,4 +16,20 @@ pub enum ConfigError {
     ParseFloat(#[from] ParseFloatError),
     #[error("Failed to parse '{0}' as hex color")]
     ParseColor(String),
+    #[error("Failed to parse '{0}', expected a comma separated list of 'key: value'")]
+    ParseMap(String),
+    #[error("Unknown copy field '{0}', expected one of 'name', 'description' or 'query'")]
+    ParseCopyField(String),
+    #[error("Unknown skin tone '{0}', expected 'none', 'light', 'medium-light', 'medium', 'medium-dark' or 'dark'")]
+    ParseSkinTone(String),
+    #[error("Invalid font weight '{0}', expected 'normal', 'bold' or a number between 1 and 1000")]
+    ParseFontWeight(String),
+    #[error("Unknown anchor '{0}', expected 'top', 'center' or 'cursor'")]
+    ParseAnchor(String),
+    #[error("Unknown emoji action '{0}', expected 'copy' or 'type'")]
+    ParseEmojiAction(String),
+    #[error("Script mode is missing the '--{0}' property")]
+    MissingScriptProperty(&'static str),
+    #[error("Web engine is missing the '--{0}' property")]
+    MissingWebEngineProperty(&'static str),
 }
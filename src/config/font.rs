@@ -0,0 +1,112 @@
+use crate::font;
+use iced::Font;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FontStyle {
+    Normal,
+    Italic,
+    Oblique,
+}
+
+// Font of a text element, set with `font-family`, `font-weight` and `font-style`.
+// Elements without `font-family` use the `--font-family` of the theme.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OnagreFont {
+    pub family: Option<String>,
+    pub weight: u16,
+    pub style: FontStyle,
+}
+
+impl OnagreFont {
+    pub const NORMAL: u16 = 400;
+    pub const BOLD: u16 = 700;
+
+    // The application default font is used unless a font property is set
+    pub fn is_default(&self) -> bool {
+        *self == OnagreFont::default()
+    }
+
+    // The font used to render `text`, a fallback font is used if some glyphs are missing
+    pub fn for_text(&self, text: &str) -> Font {
+        font::for_text(self, text)
+    }
+}
+
+impl Default for OnagreFont {
+    fn default() -> Self {
+        OnagreFont {
+            family: None,
+            weight: OnagreFont::NORMAL,
+            style: FontStyle::Normal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::config::error::ConfigError;
+    use crate::config::font::{FontStyle, OnagreFont};
+    use crate::config::helpers::{unwrap_font_style, unwrap_font_weight};
+    use crate::config::{Rule, ThemeParser};
+    use pest::Parser;
+    use speculoos::prelude::*;
+
+    fn font_weight(input: &str) -> Result<u16, ConfigError> {
+        let pair = ThemeParser::parse(Rule::font_weight, input)
+            .unwrap()
+            .next()
+            .unwrap();
+
+        unwrap_font_weight(pair)
+    }
+
+    fn font_style(input: &str) -> FontStyle {
+        let pair = ThemeParser::parse(Rule::font_style, input)
+            .unwrap()
+            .next()
+            .unwrap();
+
+        unwrap_font_style(pair)
+    }
+
+    #[test]
+    fn should_parse_font_weight() {
+        assert_that!(font_weight("font-weight: normal;")).is_ok_containing(OnagreFont::NORMAL);
+        assert_that!(font_weight("font-weight: bold;")).is_ok_containing(OnagreFont::BOLD);
+        assert_that!(font_weight("font-weight: 600;")).is_ok_containing(600);
+        assert_that!(font_weight("font-weight: 1000;")).is_ok_containing(1000);
+    }
+
+    #[test]
+    fn should_reject_invalid_font_weight() {
+        assert_that!(matches!(
+            font_weight("font-weight: 0;"),
+            Err(ConfigError::ParseFontWeight(_))
+        ))
+        .is_true();
+        assert_that!(ThemeParser::parse(
+            Rule::font_weight,
+            "font-weight: bolder;"
+        ))
+        .is_err();
+    }
+
+    #[test]
+    fn should_parse_font_style() {
+        assert_that!(font_style("font-style: normal;")).is_equal_to(FontStyle::Normal);
+        assert_that!(font_style("font-style: italic;")).is_equal_to(FontStyle::Italic);
+        assert_that!(font_style("font-style: oblique;")).is_equal_to(FontStyle::Oblique);
+        assert_that!(ThemeParser::parse(Rule::font_style, "font-style: slanted;")).is_err();
+    }
+
+    #[test]
+    fn should_only_use_default_font_without_font_properties() {
+        let bold = OnagreFont {
+            weight: OnagreFont::BOLD,
+            ..Default::default()
+        };
+
+        assert_that!(OnagreFont::default().is_default()).is_true();
+        assert_that!(bold.is_default()).is_false();
+    }
+}
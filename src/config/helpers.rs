@@ -1,9 +1,17 @@
+use crate::app::animation::{Easing, Transition};
+use crate::app::clipboard::CopyField;
+use crate::app::placement::{Anchor, Monitor, Offset};
+use crate::app::style::scrollable::RowLayout;
 use crate::config::color::OnagreColor;
 use crate::config::error::ConfigError;
+use crate::config::font::{FontStyle, OnagreFont};
 use crate::config::Rule;
+use crate::emoji::{EmojiAction, SkinTone};
 use iced::alignment::{Horizontal, Vertical};
 use iced::Length;
 use pest::iterators::Pair;
+use std::collections::HashMap;
+use std::time::Duration;
 
 // Helper functions to get values from the css like theme config file.
 // We don't mind unwrapping here since pest
@@ -42,12 +50,153 @@ pub fn unwrap_attr_str(pair: Pair<'_, Rule>) -> &str {
     pair.into_inner().last().unwrap().into_inner().as_str()
 }
 
+// Parse a string attribute formatted as "value, value"
+pub fn unwrap_attr_list(pair: Pair<'_, Rule>) -> Vec<String> {
+    unwrap_attr_str(pair)
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// Parse a string attribute formatted as "key: value, key: value"
+pub fn unwrap_attr_map(pair: Pair<'_, Rule>) -> Result<HashMap<String, String>, ConfigError> {
+    let value = unwrap_attr_str(pair);
+    let mut map = HashMap::new();
+
+    for entry in value.split(',').filter(|entry| !entry.trim().is_empty()) {
+        let (key, value) = entry
+            .split_once(':')
+            .ok_or_else(|| ConfigError::ParseMap(value.to_string()))?;
+
+        map.insert(key.trim().to_string(), value.trim().to_string());
+    }
+
+    Ok(map)
+}
+
+pub fn unwrap_attr_u32_map(pair: Pair<'_, Rule>) -> Result<HashMap<String, u32>, ConfigError> {
+    unwrap_attr_map(pair)?
+        .into_iter()
+        .map(|(key, value)| Ok((key, value.parse::<u32>()?)))
+        .collect()
+}
+
+pub fn unwrap_copy_fields(pair: Pair<'_, Rule>) -> Result<HashMap<String, CopyField>, ConfigError> {
+    unwrap_attr_map(pair)?
+        .into_iter()
+        .map(|(mode, field)| {
+            let field = match field.as_str() {
+                "name" => CopyField::Name,
+                "description" => CopyField::Description,
+                "query" => CopyField::Query,
+                _ => return Err(ConfigError::ParseCopyField(field)),
+            };
+
+            Ok((mode, field))
+        })
+        .collect()
+}
+
+pub fn unwrap_skin_tone(pair: Pair<'_, Rule>) -> Result<Option<SkinTone>, ConfigError> {
+    match unwrap_attr_str(pair) {
+        "none" => Ok(None),
+        "light" => Ok(Some(SkinTone::Light)),
+        "medium-light" => Ok(Some(SkinTone::MediumLight)),
+        "medium" => Ok(Some(SkinTone::Medium)),
+        "medium-dark" => Ok(Some(SkinTone::MediumDark)),
+        "dark" => Ok(Some(SkinTone::Dark)),
+        other => Err(ConfigError::ParseSkinTone(other.to_string())),
+    }
+}
+
+pub fn unwrap_emoji_action(pair: Pair<'_, Rule>) -> Result<EmojiAction, ConfigError> {
+    match unwrap_attr_str(pair) {
+        "copy" => Ok(EmojiAction::Copy),
+        "type" => Ok(EmojiAction::Type),
+        other => Err(ConfigError::ParseEmojiAction(other.to_string())),
+    }
+}
+
+pub fn unwrap_anchor(pair: Pair<'_, Rule>) -> Result<Anchor, ConfigError> {
+    match unwrap_attr_str(pair) {
+        "top" => Ok(Anchor::Top),
+        "center" => Ok(Anchor::Center),
+        "cursor" => Ok(Anchor::Cursor),
+        other => Err(ConfigError::ParseAnchor(other.to_string())),
+    }
+}
+
+// Offsets are either pixels or a percentage of the monitor size
+pub fn unwrap_offset(pair: Pair<'_, Rule>) -> Result<Offset, ConfigError> {
+    let value = pair.into_inner().last().unwrap().as_str();
+
+    match value.strip_suffix('%') {
+        Some(percent) => Ok(Offset::Percent(percent.parse::<f32>()?)),
+        None => Ok(Offset::Pixels(value.trim_end_matches("px").parse::<i32>()?)),
+    }
+}
+
+pub fn unwrap_monitor(pair: Pair<'_, Rule>) -> Monitor {
+    match unwrap_attr_str(pair) {
+        "focused" => Monitor::Focused,
+        "primary" => Monitor::Primary,
+        name => Monitor::Named(name.to_string()),
+    }
+}
+
+// The grammar only accepts `list` and `grid`
+pub fn unwrap_layout(pair: Pair<'_, Rule>) -> RowLayout {
+    match pair.into_inner().last().unwrap().as_str() {
+        "grid" => RowLayout::Grid,
+        _ => RowLayout::List,
+    }
+}
+
+// Parse a `<duration>ms [easing]` transition, easing defaults to `ease` like in CSS
+pub fn unwrap_transition(pair: Pair<'_, Rule>) -> Result<Transition, ConfigError> {
+    let mut value = pair.into_inner().last().unwrap().into_inner();
+    let duration = value.next().unwrap().as_str().parse::<u64>()?;
+    let easing = match value.next().map(|easing| easing.as_str()) {
+        Some("linear") => Easing::Linear,
+        Some("ease-in") => Easing::EaseIn,
+        Some("ease-out") => Easing::EaseOut,
+        Some("ease-in-out") => Easing::EaseInOut,
+        _ => Easing::Ease,
+    };
+
+    Ok(Transition {
+        duration: Duration::from_millis(duration),
+        easing,
+    })
+}
+
 pub fn unwrap_attr_bool(pair: Pair<'_, Rule>) -> bool {
     let value = pair.into_inner().last().unwrap().as_str();
 
     value == "true"
 }
 
+pub fn unwrap_font_weight(pair: Pair<'_, Rule>) -> Result<u16, ConfigError> {
+    match pair.into_inner().last().unwrap().as_str() {
+        "normal" => Ok(OnagreFont::NORMAL),
+        "bold" => Ok(OnagreFont::BOLD),
+        value => match value.parse::<u16>()? {
+            weight @ 1..=1000 => Ok(weight),
+            _ => Err(ConfigError::ParseFontWeight(value.to_string())),
+        },
+    }
+}
+
+pub fn unwrap_font_style(pair: Pair<'_, Rule>) -> FontStyle {
+    match pair.into_inner().last().unwrap().as_str() {
+        "italic" => FontStyle::Italic,
+        "oblique" => FontStyle::Oblique,
+        _ => FontStyle::Normal,
+    }
+}
+
 pub fn unwrap_hex_color(pair: Pair<'_, Rule>) -> Result<OnagreColor, ConfigError> {
     let color = pair.into_inner().last().unwrap().as_str();
 
@@ -101,16 +250,20 @@ pub fn unwrap_length(pair: Pair<'_, Rule>) -> Result<Length, ConfigError> {
 
 #[cfg(test)]
 mod test {
+    use crate::app::clipboard::CopyField;
     use crate::config::color::OnagreColor;
     use crate::config::helpers::{
-        unwrap_attr_bool, unwrap_attr_f32, unwrap_attr_str, unwrap_attr_u16, unwrap_hex_color,
-        unwrap_length, unwrap_x, unwrap_y,
+        unwrap_attr_32, unwrap_attr_bool, unwrap_attr_f32, unwrap_attr_str, unwrap_attr_u16,
+        unwrap_attr_u32_map, unwrap_copy_fields, unwrap_hex_color, unwrap_length, unwrap_skin_tone,
+        unwrap_x, unwrap_y,
     };
     use crate::config::{Rule, ThemeParser};
+    use crate::emoji::SkinTone;
     use iced::alignment::{Horizontal, Vertical};
     use iced::Length;
     use pest::Parser;
     use speculoos::prelude::*;
+    use std::collections::HashMap;
 
     #[test]
     fn should_parse_align_x() {
@@ -513,4 +666,132 @@ mod test {
             .that(&exit_unfocused)
             .is_true();
     }
+
+    #[test]
+    fn should_parse_search_debounce() {
+        let pair = ThemeParser::parse(Rule::search_debounce, "--search-debounce: 200ms;")
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let search_debounce = unwrap_attr_32(pair);
+
+        asserting!("Should parse 'search_debounce' attribute")
+            .that(&search_debounce)
+            .is_ok()
+            .is_equal_to(200);
+    }
+
+    #[test]
+    fn should_parse_debounced_plugins() {
+        let pair = ThemeParser::parse(
+            Rule::debounced_plugins,
+            r#"--debounced-plugins: "find, recent";"#,
+        )
+        .unwrap()
+        .next()
+        .unwrap();
+
+        let debounced_plugins = unwrap_attr_str(pair);
+
+        asserting!("Should parse 'debounced_plugins' attribute")
+            .that(&debounced_plugins)
+            .is_equal_to("find, recent");
+    }
+
+    #[test]
+    fn should_parse_max_results() {
+        let pair = ThemeParser::parse(
+            Rule::max_results,
+            r#"--max-results: "history: 20, web: 5";"#,
+        )
+        .unwrap()
+        .next()
+        .unwrap();
+
+        let max_results = unwrap_attr_u32_map(pair);
+
+        asserting!("Should parse 'max_results' attribute")
+            .that(&max_results)
+            .is_ok()
+            .is_equal_to(HashMap::from([
+                ("history".to_string(), 20),
+                ("web".to_string(), 5),
+            ]));
+    }
+
+    #[test]
+    fn should_fail_to_parse_invalid_max_results() {
+        let pair = ThemeParser::parse(Rule::max_results, r#"--max-results: "history 20";"#)
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let max_results = unwrap_attr_u32_map(pair);
+
+        assert_that!(max_results).is_err();
+    }
+
+    #[test]
+    fn should_parse_lines() {
+        let pair = ThemeParser::parse(Rule::lines, "--lines: 8;")
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let lines = unwrap_attr_u16(pair);
+
+        asserting!("Should parse 'lines' attribute")
+            .that(&lines)
+            .is_ok()
+            .is_equal_to(8);
+    }
+
+    #[test]
+    fn should_parse_copy_fields() {
+        let pair = ThemeParser::parse(
+            Rule::copy_fields,
+            r#"--copy-fields: "web: query, calc: name";"#,
+        )
+        .unwrap()
+        .next()
+        .unwrap();
+
+        let copy_fields = unwrap_copy_fields(pair);
+
+        asserting!("Should parse 'copy_fields' attribute")
+            .that(&copy_fields)
+            .is_ok()
+            .is_equal_to(HashMap::from([
+                ("web".to_string(), CopyField::Query),
+                ("calc".to_string(), CopyField::Name),
+            ]));
+    }
+
+    #[test]
+    fn should_parse_skin_tone() {
+        let pair = ThemeParser::parse(Rule::skin_tone, r#"--skin-tone: "medium-dark";"#)
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let skin_tone = unwrap_skin_tone(pair);
+
+        asserting!("Should parse 'skin_tone' attribute")
+            .that(&skin_tone)
+            .is_ok()
+            .is_equal_to(Some(SkinTone::MediumDark));
+    }
+
+    #[test]
+    fn should_fail_to_parse_unknown_copy_field() {
+        let pair = ThemeParser::parse(Rule::copy_fields, r#"--copy-fields: "web: url";"#)
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let copy_fields = unwrap_copy_fields(pair);
+
+        assert_that!(copy_fields).is_err();
+    }
 }
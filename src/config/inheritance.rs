@@ -60,6 +60,10 @@ impl Inherit for AppContainerStyles {
         self.search.propagate_background();
 
         self.scrollable.background = self.background;
+
+        if let Some(preview) = &mut self.preview {
+            preview.background = self.background;
+        }
     }
 
     fn propagate_color(&mut self) {
@@ -68,6 +72,10 @@ impl Inherit for AppContainerStyles {
         self.search.color = self.color;
         self.search.propagate_color();
         self.scrollable.scroller_color = self.color;
+
+        if let Some(preview) = &mut self.preview {
+            preview.color = self.color;
+        }
     }
 }
 
@@ -78,6 +86,8 @@ impl Inherit for RowContainerStyle {
 
         self.row_selected.background = self.background;
         self.row_selected.propagate_background();
+
+        self.section_header.background = self.background;
     }
 
     fn propagate_color(&mut self) {
@@ -86,6 +96,8 @@ impl Inherit for RowContainerStyle {
 
         self.row_selected.color = self.color;
         self.row_selected.propagate_color();
+
+        self.section_header.color = self.color;
     }
 }
 
@@ -95,6 +107,7 @@ impl Inherit for RowStyles {
         self.description.background = self.background;
         self.icon.background = self.background;
         self.category_icon.background = self.background;
+        self.shortcut.background = self.background;
     }
 
     fn propagate_color(&mut self) {
@@ -102,6 +115,7 @@ impl Inherit for RowStyles {
         self.description.color = self.color;
         self.icon.color = self.color;
         self.category_icon.color = self.color;
+        self.shortcut.color = self.color;
     }
 }
 
@@ -1,5 +1,6 @@
 pub mod color;
 mod error;
+pub mod font;
 mod helpers;
 mod inheritance;
 pub mod padding;
@@ -21,6 +22,8 @@ use crate::app::style::scrollable::RowContainerStyle;
 use crate::app::style::search::input::SearchInputStyles;
 use crate::app::style::search::SearchContainerStyles;
 use crate::app::style::Theme;
+use crate::engines::WebEngine;
+use crate::scripts::ScriptMode;
 use padding::OnagrePadding;
 
 #[derive(Parser)]
@@ -59,20 +62,49 @@ impl TryFrom<Pair<'_, Rule>> for Theme {
         for pair in pair.into_inner() {
             match pair.as_rule() {
                 Rule::exit_unfocused => theme.exit_unfocused = helpers::unwrap_attr_bool(pair),
+                Rule::search_debounce => theme.search_debounce = helpers::unwrap_attr_32(pair)?,
+                Rule::max_results => theme.max_results = helpers::unwrap_attr_u32_map(pair)?,
+                Rule::placeholders => theme.placeholders = helpers::unwrap_attr_map(pair)?,
+                Rule::prompt => {
+                    let prompt = helpers::unwrap_attr_str(pair);
+                    theme.prompt = Some(prompt.to_string()).filter(|prompt| !prompt.is_empty())
+                }
+                Rule::empty_message => {
+                    theme.empty_message = helpers::unwrap_attr_str(pair).to_string()
+                }
+                Rule::copy_fields => theme.copy_fields = helpers::unwrap_copy_fields(pair)?,
+                Rule::terminal => theme.terminal = Some(helpers::unwrap_attr_str(pair).to_string()),
+                Rule::skin_tone => theme.skin_tone = helpers::unwrap_skin_tone(pair)?,
+                Rule::emoji_action => theme.emoji_action = helpers::unwrap_emoji_action(pair)?,
+                Rule::debounced_plugins => {
+                    theme.debounced_plugins = helpers::unwrap_attr_list(pair)
+                }
                 Rule::font_family => theme.font = Some(helpers::unwrap_attr_str(pair).to_string()),
                 Rule::font_size => {
                     theme.font_size = helpers::unwrap_attr_u16(pair)?;
                     theme.propagate_font_size();
                 }
-                Rule::icon_theme => {
-                    theme.icon_theme = Some(helpers::unwrap_attr_str(pair).to_string())
+                Rule::icon_theme => theme.icon_theme = Some(helpers::unwrap_attr_list(pair)),
+                Rule::fallback_icons => {
+                    theme.fallback_icons.extend(helpers::unwrap_attr_map(pair)?)
                 }
                 Rule::icon_size => {
                     theme.icon_size = helpers::unwrap_attr_u16(pair)?;
                     theme.propagate_icon_size();
                 }
+                Rule::scale => theme.scale = helpers::unwrap_attr_f32(pair)?,
+                Rule::anchor => theme.placement.anchor = helpers::unwrap_anchor(pair)?,
+                Rule::offset_x => theme.placement.offset_x = helpers::unwrap_offset(pair)?,
+                Rule::offset_y => theme.placement.offset_y = helpers::unwrap_offset(pair)?,
+                Rule::monitor => theme.placement.monitor = helpers::unwrap_monitor(pair),
+                Rule::floating => theme.placement.floating = helpers::unwrap_attr_bool(pair),
+                Rule::transition => theme.transition = Some(helpers::unwrap_transition(pair)?),
+                Rule::reduce_motion => theme.reduce_motion = helpers::unwrap_attr_bool(pair),
                 Rule::window_height => theme.size.1 = helpers::unwrap_attr_32(pair)?,
                 Rule::window_width => theme.size.0 = helpers::unwrap_attr_32(pair)?,
+                Rule::auto_height => theme.auto_height = true,
+                Rule::min_rows => theme.min_rows = helpers::unwrap_attr_32(pair)?,
+                Rule::max_rows => theme.max_rows = helpers::unwrap_attr_32(pair)?,
                 Rule::background => {
                     theme.background = helpers::unwrap_hex_color(pair)?;
                     theme.propagate_background();
@@ -91,6 +123,8 @@ impl TryFrom<Pair<'_, Rule>> for Theme {
                 Rule::padding_bottom => theme.padding.bottom = helpers::unwrap_attr_u16(pair)?,
                 Rule::padding_right => theme.padding.right = helpers::unwrap_attr_u16(pair)?,
                 Rule::padding_left => theme.padding.left = helpers::unwrap_attr_u16(pair)?,
+                Rule::script_mode => theme.script_modes.push(ScriptMode::try_from(pair)?),
+                Rule::web_engine => theme.web_engines.push(WebEngine::try_from(pair)?),
                 Rule::container => theme.app_container.apply(pair)?,
                 Rule::EOI => break,
                 _ => unreachable!(),
@@ -101,6 +135,63 @@ impl TryFrom<Pair<'_, Rule>> for Theme {
     }
 }
 
+impl TryFrom<Pair<'_, Rule>> for ScriptMode {
+    type Error = ConfigError;
+    fn try_from(pair: Pair<'_, Rule>) -> Result<Self, Self::Error> {
+        let mut mode = ScriptMode::default();
+
+        for pair in pair.into_inner() {
+            match pair.as_rule() {
+                Rule::script_name => mode.name = helpers::unwrap_attr_str(pair).to_string(),
+                Rule::script_prefix => mode.prefix = helpers::unwrap_attr_str(pair).to_string(),
+                Rule::script_icon => mode.icon = Some(helpers::unwrap_attr_str(pair).to_string()),
+                Rule::script_command => mode.command = helpers::unwrap_attr_str(pair).to_string(),
+                _ => unreachable!(),
+            }
+        }
+
+        if mode.name.is_empty() {
+            Err(ConfigError::MissingScriptProperty("name"))
+        } else if mode.prefix.is_empty() {
+            Err(ConfigError::MissingScriptProperty("prefix"))
+        } else if mode.command.is_empty() {
+            Err(ConfigError::MissingScriptProperty("command"))
+        } else {
+            Ok(mode)
+        }
+    }
+}
+
+impl TryFrom<Pair<'_, Rule>> for WebEngine {
+    type Error = ConfigError;
+    fn try_from(pair: Pair<'_, Rule>) -> Result<Self, Self::Error> {
+        let mut engine = WebEngine::default();
+
+        for pair in pair.into_inner() {
+            match pair.as_rule() {
+                Rule::script_name => engine.name = helpers::unwrap_attr_str(pair).to_string(),
+                Rule::script_prefix => engine.prefix = helpers::unwrap_attr_str(pair).to_string(),
+                Rule::script_icon => engine.icon = Some(helpers::unwrap_attr_str(pair).to_string()),
+                Rule::engine_url => engine.url = helpers::unwrap_attr_str(pair).to_string(),
+                Rule::engine_suggestions => {
+                    engine.suggestions = Some(helpers::unwrap_attr_str(pair).to_string())
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        if engine.name.is_empty() {
+            Err(ConfigError::MissingWebEngineProperty("name"))
+        } else if engine.prefix.is_empty() {
+            Err(ConfigError::MissingWebEngineProperty("prefix"))
+        } else if engine.url.is_empty() {
+            Err(ConfigError::MissingWebEngineProperty("url"))
+        } else {
+            Ok(engine)
+        }
+    }
+}
+
 impl ApplyConfig for AppContainerStyles {
     fn apply(&mut self, pair: Pair<'_, Rule>) -> Result<(), ConfigError> {
         for pair in pair.into_inner() {
@@ -129,6 +220,17 @@ impl ApplyConfig for AppContainerStyles {
                 Rule::search => self.search.apply(pair)?,
                 Rule::rows => self.rows.apply(pair)?,
                 Rule::scrollable => self.scrollable.apply(pair)?,
+                Rule::preview => {
+                    self.preview = {
+                        let mut preview = GenericContainerStyle {
+                            background: self.background,
+                            color: self.color,
+                            ..GenericContainerStyle::preview_default()
+                        };
+                        preview.apply(pair)?;
+                        Some(preview)
+                    }
+                }
                 _ => unreachable!(),
             }
         }
@@ -241,6 +343,11 @@ impl ApplyConfig for SearchInputStyles {
                     self.placeholder_color = helpers::unwrap_hex_color(pair)?
                 }
                 Rule::font_size => self.font_size = helpers::unwrap_attr_u16(pair)?,
+                Rule::text_font_family => {
+                    self.font.family = Some(helpers::unwrap_attr_str(pair).to_string())
+                }
+                Rule::font_weight => self.font.weight = helpers::unwrap_font_weight(pair)?,
+                Rule::font_style => self.font.style = helpers::unwrap_font_style(pair),
 
                 // Layout
                 Rule::padding => {
@@ -289,10 +396,15 @@ impl ApplyConfig for RowContainerStyle {
                 Rule::padding_left => self.padding.left = helpers::unwrap_attr_u16(pair)?,
                 Rule::width => self.width = helpers::unwrap_length(pair)?,
                 Rule::height => self.height = helpers::unwrap_length(pair)?,
+                Rule::lines => self.lines = helpers::unwrap_attr_u16(pair)?,
+                Rule::layout => self.layout = helpers::unwrap_layout(pair),
+                Rule::columns => self.columns = helpers::unwrap_attr_u16(pair)?,
+                Rule::group_results => self.group_results = helpers::unwrap_attr_bool(pair),
 
                 // Children
                 Rule::default_row => self.row.apply(pair)?,
                 Rule::selected_row => self.row_selected.apply(pair)?,
+                Rule::section_header => self.section_header.apply(pair)?,
                 _ => unreachable!(),
             }
         }
@@ -344,6 +456,10 @@ impl ApplyConfig for RowStyles {
                     self.hide_category_icon = false;
                     self.category_icon.apply(pair)?
                 }
+                Rule::shortcut => {
+                    self.hide_shortcut = false;
+                    self.shortcut.apply(pair)?
+                }
                 _ => unreachable!(),
             }
         }
@@ -375,6 +491,11 @@ impl ApplyConfig for GenericContainerStyle {
                 Rule::width => self.width = helpers::unwrap_length(pair)?,
                 Rule::height => self.height = helpers::unwrap_length(pair)?,
                 Rule::font_size => self.font_size = helpers::unwrap_attr_u16(pair)?,
+                Rule::text_font_family => {
+                    self.font.family = Some(helpers::unwrap_attr_str(pair).to_string())
+                }
+                Rule::font_weight => self.font.weight = helpers::unwrap_font_weight(pair)?,
+                Rule::font_style => self.font.style = helpers::unwrap_font_style(pair),
                 _ => unreachable!(),
             }
         }
@@ -424,11 +545,13 @@ impl Theme {
                     row: RowStyles {
                         hide_description: true,
                         hide_category_icon: true,
+                        hide_shortcut: true,
                         ..Default::default()
                     },
                     row_selected: RowStyles {
                         hide_description: true,
                         hide_category_icon: true,
+                        hide_shortcut: true,
                         ..Default::default()
                     },
                     ..Default::default()
@@ -439,3 +562,336 @@ impl Theme {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::app::animation::{Easing, Transition};
+    use crate::app::placement::{Anchor, Monitor, Offset, Placement};
+    use crate::app::style::rows::generic::GenericContainerStyle;
+    use crate::app::style::scrollable::{RowContainerStyle, RowLayout};
+    use crate::app::style::Theme;
+    use crate::config::error::ConfigError;
+    use crate::config::font::{FontStyle, OnagreFont};
+    use crate::config::{ApplyConfig, Rule, ThemeParser};
+    use crate::engines::WebEngine;
+    use crate::scripts::ScriptMode;
+    use pest::Parser;
+    use speculoos::prelude::*;
+    use std::convert::TryFrom;
+    use std::time::Duration;
+
+    #[test]
+    fn should_parse_script_mode() {
+        let input = r#".script-mode {
+            --name: "notes";
+            --prefix: "n ";
+            --icon: "accessories-text-editor";
+            --command: "~/bin/notes.sh --list";
+        }"#;
+
+        let pair = ThemeParser::parse(Rule::script_mode, input)
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let mode = ScriptMode::try_from(pair);
+
+        assert_that!(mode).is_ok_containing(ScriptMode {
+            name: "notes".to_string(),
+            prefix: "n ".to_string(),
+            icon: Some("accessories-text-editor".to_string()),
+            command: "~/bin/notes.sh --list".to_string(),
+        });
+    }
+
+    #[test]
+    fn should_reject_script_mode_without_command() {
+        let input = r#".script-mode { --name: "notes"; --prefix: "n "; }"#;
+
+        let pair = ThemeParser::parse(Rule::script_mode, input)
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let mode = ScriptMode::try_from(pair);
+
+        assert_that!(matches!(
+            mode,
+            Err(ConfigError::MissingScriptProperty("command"))
+        ))
+        .is_true();
+    }
+
+    #[test]
+    fn should_parse_web_engine() {
+        let input = r#".web-engine {
+            --name: "grafana";
+            --prefix: "graf ";
+            --url: "https://grafana.example.com/dashboards?query={query}";
+            --suggestions: "https://grafana.example.com/api/suggest?q={query}";
+        }"#;
+
+        let pair = ThemeParser::parse(Rule::web_engine, input)
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let engine = WebEngine::try_from(pair);
+
+        assert_that!(engine).is_ok_containing(WebEngine {
+            name: "grafana".to_string(),
+            prefix: "graf ".to_string(),
+            icon: None,
+            url: "https://grafana.example.com/dashboards?query={query}".to_string(),
+            suggestions: Some("https://grafana.example.com/api/suggest?q={query}".to_string()),
+        });
+    }
+
+    #[test]
+    fn should_parse_fractional_scale() {
+        let input = r#".onagre {
+            --scale: 1.5;
+            --icon-size: 24px;
+        }"#;
+
+        let pair = ThemeParser::parse(Rule::onagre_style, input)
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let theme = Theme::try_from(pair);
+
+        assert_that!(theme.map(|theme| (theme.scale, theme.icon_size))).is_ok_containing((1.5, 24));
+    }
+
+    #[test]
+    fn should_parse_icon_theme_fallbacks() {
+        let input = r#".onagre {
+            --icon-theme: "Papirus, Adwaita,";
+            --fallback-icons: "desktop: application-x-executable, plugin: system-run";
+        }"#;
+
+        let pair = ThemeParser::parse(Rule::onagre_style, input)
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let theme = Theme::try_from(pair).unwrap();
+
+        assert_that!(theme.icon_theme)
+            .is_equal_to(Some(vec!["Papirus".to_string(), "Adwaita".to_string()]));
+        assert_that!(theme.fallback_icons.get("plugin").cloned())
+            .is_equal_to(Some("system-run".to_string()));
+        assert_that!(theme.fallback_icons.get("bookmark").cloned())
+            .is_equal_to(Some("web-browser".to_string()));
+    }
+
+    #[test]
+    fn should_parse_window_placement() {
+        let input = r#".onagre {
+            --anchor: "top";
+            --offset-x: -20px;
+            --offset-y: 12.5%;
+            --monitor: "HDMI-1";
+            --floating: false;
+        }"#;
+
+        let pair = ThemeParser::parse(Rule::onagre_style, input)
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let theme = Theme::try_from(pair);
+
+        assert_that!(theme.map(|theme| theme.placement)).is_ok_containing(Placement {
+            anchor: Anchor::Top,
+            offset_x: Offset::Pixels(-20),
+            offset_y: Offset::Percent(12.5),
+            monitor: Monitor::Named("HDMI-1".to_string()),
+            floating: false,
+        });
+    }
+
+    #[test]
+    fn should_fit_window_to_rows_with_auto_height() {
+        let input = r#".onagre {
+            --height: auto;
+            --min-rows: 2;
+            --max-rows: 5;
+        }"#;
+
+        let pair = ThemeParser::parse(Rule::onagre_style, input)
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let theme = Theme::try_from(pair).unwrap();
+        let row = theme.row_height(false);
+        let height = |rows| theme.auto_height(&vec![row; rows]).unwrap();
+
+        assert_that!(theme.visible_rows()).is_equal_to(5);
+        assert_that!(height(0)).is_equal_to(height(2));
+        assert_that!(height(3)).is_greater_than(height(2));
+        assert_that!(height(42)).is_equal_to(height(5));
+        assert_that!(Theme::default().auto_height(&[row; 3])).is_none();
+    }
+
+    #[test]
+    fn should_fit_window_to_rows_with_and_without_description() {
+        let input = r#".onagre {
+            --height: auto;
+            --max-rows: 5;
+        }"#;
+
+        let pair = ThemeParser::parse(Rule::onagre_style, input)
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let mut theme = Theme::try_from(pair).unwrap();
+        // Icons taller than the text would hide the description height
+        let rows = &mut theme.app_container.rows;
+        for row in [&mut rows.row, &mut rows.row_selected].iter_mut() {
+            row.hide_description = false;
+            row.icon.icon_size = 8;
+        }
+
+        let title_only = theme.row_height(false);
+        let described = theme.row_height(true);
+        let height = |lines: &[f32]| theme.auto_height(lines).unwrap();
+
+        assert_that!(described).is_greater_than(title_only);
+        assert_that!(height(&[title_only, described]))
+            .is_less_than(height(&[described, described]));
+        assert_that!(height(&[described; 3])).is_greater_than(height(&[title_only; 3]));
+    }
+
+    #[test]
+    fn should_disable_transition_with_reduce_motion() {
+        let input = r#".onagre {
+            --transition: 120ms ease-out;
+        }"#;
+
+        let pair = ThemeParser::parse(Rule::onagre_style, input)
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let mut theme = Theme::try_from(pair).unwrap();
+
+        assert_that!(theme.transition().copied()).is_equal_to(Some(Transition {
+            duration: Duration::from_millis(120),
+            easing: Easing::EaseOut,
+        }));
+
+        theme.reduce_motion = true;
+        assert_that!(theme.transition()).is_none();
+    }
+
+    #[test]
+    fn should_parse_title_font() {
+        let input = r#".title {
+            font-family: "Noto Sans";
+            font-weight: bold;
+            font-style: italic;
+        }"#;
+
+        let pair = ThemeParser::parse(Rule::title_row, input)
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let mut title = GenericContainerStyle::default();
+        title.apply(pair).unwrap();
+
+        assert_that!(title.font).is_equal_to(OnagreFont {
+            family: Some("Noto Sans".to_string()),
+            weight: OnagreFont::BOLD,
+            style: FontStyle::Italic,
+        });
+    }
+
+    #[test]
+    fn should_reject_out_of_range_font_weight() {
+        let input = r#".title { font-weight: 1200; }"#;
+
+        let pair = ThemeParser::parse(Rule::title_row, input)
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let mut title = GenericContainerStyle::default();
+
+        assert_that!(matches!(
+            title.apply(pair),
+            Err(ConfigError::ParseFontWeight(_))
+        ))
+        .is_true();
+    }
+
+    #[test]
+    fn should_parse_grid_layout() {
+        let input = r#".rows {
+            --layout: grid;
+            --columns: 4;
+        }"#;
+
+        let pair = ThemeParser::parse(Rule::rows, input)
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let mut rows = RowContainerStyle::default();
+        rows.apply(pair).unwrap();
+
+        assert_that!(rows.layout).is_equal_to(RowLayout::Grid);
+        assert_that!(rows.columns()).is_equal_to(4);
+        assert_that!(RowContainerStyle::default().columns()).is_equal_to(1);
+    }
+
+    #[test]
+    fn should_parse_grouped_results_with_section_header() {
+        let input = r#".rows {
+            color: #ff0000;
+            --group-results: true;
+            .section-header {
+                font-size: 12;
+            }
+        }"#;
+
+        let pair = ThemeParser::parse(Rule::rows, input)
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let mut rows = RowContainerStyle::default();
+        rows.apply(pair).unwrap();
+
+        assert_that!(rows.group_results).is_true();
+        assert_that!(rows.section_header.font_size).is_equal_to(12);
+        assert_that!(rows.section_header.color).is_equal_to(rows.color);
+        assert_that!(rows.color).is_not_equal_to(RowContainerStyle::default().color);
+    }
+
+    #[test]
+    fn should_parse_placeholders_prompt_and_empty_message() {
+        let input = r#".onagre {
+            --placeholders: "desktop_entries: Search apps…, find: Search files…";
+            --prompt: "❯";
+            --empty-message: "Nothing matches {query}";
+        }"#;
+
+        let pair = ThemeParser::parse(Rule::onagre_style, input)
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let theme = Theme::try_from(pair).unwrap();
+
+        assert_that!(theme.placeholder("find ")).is_equal_to(Some("Search files…"));
+        assert_that!(theme.placeholder("calc")).is_none();
+        assert_that!(theme.prompt).is_equal_to(Some("❯".to_string()));
+        assert_that!(theme.empty_message("firefx "))
+            .is_equal_to("Nothing matches firefx".to_string());
+    }
+}
@@ -16,6 +16,10 @@ impl OnagrePadding {
         left: 0,
     };
 
+    pub fn vertical(&self) -> f32 {
+        (self.top + self.bottom) as f32
+    }
+
     pub fn to_iced_padding(&self) -> Padding {
         Padding {
             top: self.top as f32,
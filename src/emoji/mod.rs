@@ -0,0 +1,194 @@
+use once_cell::sync::Lazy;
+use std::cmp::Reverse;
+
+// Database collection used to rank frequently picked characters
+pub const COLLECTION: &str = "emoji";
+
+static EMOJIS: Lazy<Vec<Emoji>> = Lazy::new(|| parse_table(include_str!("emoji.tsv")));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkinTone {
+    Light,
+    MediumLight,
+    Medium,
+    MediumDark,
+    Dark,
+}
+
+// What to do with the picked character
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmojiAction {
+    Copy,
+    Type,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Emoji {
+    pub glyph: &'static str,
+    pub name: &'static str,
+    pub keywords: Vec<&'static str>,
+    // Whether a skin tone modifier can be applied
+    pub skin_tones: bool,
+}
+
+impl SkinTone {
+    pub const ALL: [SkinTone; 5] = [
+        SkinTone::Light,
+        SkinTone::MediumLight,
+        SkinTone::Medium,
+        SkinTone::MediumDark,
+        SkinTone::Dark,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SkinTone::Light => "light skin tone",
+            SkinTone::MediumLight => "medium-light skin tone",
+            SkinTone::Medium => "medium skin tone",
+            SkinTone::MediumDark => "medium-dark skin tone",
+            SkinTone::Dark => "dark skin tone",
+        }
+    }
+
+    fn modifier(self) -> char {
+        match self {
+            SkinTone::Light => '\u{1F3FB}',
+            SkinTone::MediumLight => '\u{1F3FC}',
+            SkinTone::Medium => '\u{1F3FD}',
+            SkinTone::MediumDark => '\u{1F3FE}',
+            SkinTone::Dark => '\u{1F3FF}',
+        }
+    }
+}
+
+impl Emoji {
+    // The modifier follows the first code point, replacing its variation selector if any
+    pub fn with_skin_tone(&self, tone: Option<SkinTone>) -> String {
+        let tone = match tone {
+            Some(tone) if self.skin_tones => tone,
+            _ => return self.glyph.to_string(),
+        };
+
+        let mut chars = self.glyph.chars();
+        let mut glyph = String::new();
+        glyph.extend(chars.next());
+        glyph.push(tone.modifier());
+        let rest = chars.as_str();
+        glyph.push_str(rest.strip_prefix('\u{FE0F}').unwrap_or(rest));
+        glyph
+    }
+
+    // Every term must prefix a word of the name or a keyword,
+    // lower is better and terms only found in keywords rank last
+    fn score(&self, terms: &[String]) -> Option<(bool, usize)> {
+        let mut keyword_matches = 0;
+        for term in terms {
+            let in_name = self
+                .name
+                .split(|c: char| !c.is_alphanumeric())
+                .any(|word| word.starts_with(term.as_str()));
+
+            if in_name {
+                continue;
+            }
+
+            if self
+                .keywords
+                .iter()
+                .any(|keyword| keyword.starts_with(term.as_str()))
+            {
+                keyword_matches += 1;
+            } else {
+                return None;
+            }
+        }
+
+        let exact = self.name == terms.join(" ");
+        Some((!exact, keyword_matches))
+    }
+}
+
+// Characters matching `query`, the most used first according to `weight`
+pub fn search<W>(query: &str, weight: W) -> Vec<&'static Emoji>
+where
+    W: Fn(&Emoji) -> u8,
+{
+    let terms: Vec<String> = query
+        .to_lowercase()
+        .split_whitespace()
+        .map(str::to_string)
+        .collect();
+
+    let mut matches: Vec<_> = EMOJIS
+        .iter()
+        .filter_map(|emoji| emoji.score(&terms).map(|score| (emoji, score)))
+        .collect();
+
+    matches.sort_by_key(|(emoji, score)| (Reverse(weight(emoji)), *score));
+    matches.into_iter().map(|(emoji, _)| emoji).collect()
+}
+
+fn parse_table(table: &'static str) -> Vec<Emoji> {
+    table
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let glyph = fields.next().filter(|glyph| !glyph.is_empty())?;
+            let name = fields.next()?;
+            let keywords = fields
+                .next()
+                .unwrap_or_default()
+                .split(',')
+                .filter(|keyword| !keyword.is_empty())
+                .collect();
+            let skin_tones = fields.next() == Some("t");
+
+            Some(Emoji {
+                glyph,
+                name,
+                keywords,
+                skin_tones,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::emoji::{search, SkinTone};
+    use speculoos::prelude::*;
+
+    #[test]
+    fn should_search_names_and_keywords() {
+        let by_name = search("thumbs up", |_| 0);
+        let by_keyword = search("+1", |_| 0);
+        let symbol = search("rightwards arrow", |_| 0);
+
+        assert_that!(by_name[0].glyph).is_equal_to("👍");
+        assert_that!(by_keyword[0].glyph).is_equal_to("👍");
+        assert_that!(symbol.iter().any(|emoji| emoji.glyph == "→")).is_true();
+    }
+
+    #[test]
+    fn should_rank_frequently_used_first() {
+        let results = search("heart", |emoji| u8::from(emoji.glyph == "💜"));
+
+        assert_that!(results[0].glyph).is_equal_to("💜");
+    }
+
+    #[test]
+    fn should_apply_skin_tones() {
+        let thumbs_up = search("thumbs up", |_| 0)[0];
+        let index_up = search("index pointing up", |_| 0)[0];
+        let technologist = search("technologist", |_| 0)[0];
+        let rocket = search("rocket", |_| 0)[0];
+
+        assert_that!(thumbs_up.with_skin_tone(Some(SkinTone::Medium)))
+            .is_equal_to("👍🏽".to_string());
+        assert_that!(index_up.with_skin_tone(Some(SkinTone::Dark))).is_equal_to("☝🏿".to_string());
+        assert_that!(technologist.with_skin_tone(Some(SkinTone::Light)))
+            .is_equal_to("🧑🏻\u{200D}💻".to_string());
+        assert_that!(rocket.with_skin_tone(Some(SkinTone::Light))).is_equal_to("🚀".to_string());
+    }
+}
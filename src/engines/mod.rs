@@ -0,0 +1,162 @@
+use log::warn;
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use std::time::Duration;
+
+// Placeholder replaced by the url encoded query in `--url` and `--suggestions`
+const QUERY_PLACEHOLDER: &str = "{query}";
+
+// Suggestions are dropped if the endpoint is slower than this
+const SUGGESTIONS_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Shared by all suggestion requests to reuse its connection pool
+static CLIENT: Lazy<reqwest::Client> = Lazy::new(reqwest::Client::new);
+
+// A search engine declared in the theme with a `.web-engine` block, e.g.
+// `--url: "https://jira.example.com/issues/?jql=text~{query}"`.
+// The optional `--suggestions` endpoint must return OpenSearch suggestions:
+// `["query", ["suggestion 1", "suggestion 2"]]`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WebEngine {
+    pub name: String,
+    pub prefix: String,
+    pub icon: Option<String>,
+    pub url: String,
+    pub suggestions: Option<String>,
+}
+
+// A row of a web engine mode
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngineQuery {
+    pub query: String,
+    pub source: QuerySource,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuerySource {
+    Input,
+    History,
+    Suggestion,
+}
+
+impl WebEngine {
+    pub fn search_url(&self, query: &str) -> String {
+        fill_template(&self.url, query)
+    }
+
+    pub async fn suggestions(&self, query: String) -> Vec<String> {
+        let endpoint = match &self.suggestions {
+            Some(endpoint) if !query.trim().is_empty() => fill_template(endpoint, &query),
+            _ => return vec![],
+        };
+
+        match fetch_suggestions(&endpoint).await {
+            Ok(suggestions) => suggestions,
+            Err(err) => {
+                warn!("Failed to fetch '{}' suggestions: {err}", self.name);
+                vec![]
+            }
+        }
+    }
+}
+
+async fn fetch_suggestions(endpoint: &str) -> anyhow::Result<Vec<String>> {
+    let body = CLIENT
+        .get(endpoint)
+        .timeout(SUGGESTIONS_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    parse_suggestions(&body)
+}
+
+// OpenSearch suggestions are a json array, the second element holds the completions
+pub fn parse_suggestions(body: &str) -> anyhow::Result<Vec<String>> {
+    let json: Value = serde_json::from_str(body)?;
+    let suggestions = json
+        .get(1)
+        .and_then(Value::as_array)
+        .ok_or_else(|| anyhow::anyhow!("not an OpenSearch suggestions response"))?;
+
+    Ok(suggestions
+        .iter()
+        .filter_map(Value::as_str)
+        .map(str::to_string)
+        .collect())
+}
+
+fn fill_template(template: &str, query: &str) -> String {
+    template.replace(QUERY_PLACEHOLDER, &urlencoding::encode(query.trim()))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::engines::{parse_suggestions, WebEngine};
+    use speculoos::prelude::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn engine(suggestions: Option<String>) -> WebEngine {
+        WebEngine {
+            name: "jira".to_string(),
+            prefix: "jira ".to_string(),
+            icon: None,
+            url: "https://jira.example.com/issues/?jql=text~{query}".to_string(),
+            suggestions,
+        }
+    }
+
+    #[test]
+    fn should_fill_url_template() {
+        let url = engine(None).search_url("login & sso");
+
+        assert_that!(url)
+            .is_equal_to("https://jira.example.com/issues/?jql=text~login%20%26%20sso".to_string());
+    }
+
+    #[test]
+    fn should_reject_invalid_suggestions() {
+        assert_that!(parse_suggestions(r#"{"query": "rust"}"#)).is_err();
+        assert_that!(parse_suggestions(r#"["rust", ["rustup", 1, "rust book"]]"#).unwrap())
+            .is_equal_to(vec!["rustup".to_string(), "rust book".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn should_fetch_suggestions_from_endpoint() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        // Answers a single request and returns the request line
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let read = stream.read(&mut request).unwrap();
+            let body = r#"["grafana", ["grafana alerts", "grafana dashboards"]]"#;
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/x-suggestions+json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+
+            let request = String::from_utf8_lossy(&request[..read]).to_string();
+            request.lines().next().unwrap_or_default().to_string()
+        });
+
+        let endpoint = format!("http://127.0.0.1:{port}/suggest?q={{query}}");
+        let suggestions = engine(Some(endpoint))
+            .suggestions("grafana".to_string())
+            .await;
+
+        assert_that!(server.join().unwrap())
+            .is_equal_to("GET /suggest?q=grafana HTTP/1.1".to_string());
+        assert_that!(suggestions).is_equal_to(vec![
+            "grafana alerts".to_string(),
+            "grafana dashboards".to_string(),
+        ]);
+    }
+}
@@ -0,0 +1,11 @@
+use std::fs;
+use std::path::PathBuf;
+
+// An empty directory for the files of a test, named after the process id
+// so concurrent test runs don't write to the same fixture
+pub fn fixture_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("onagre-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -1,7 +1,14 @@
+use crate::config::font::{FontStyle, OnagreFont};
+use crate::THEME;
 use font_kit::family_name::FamilyName;
 use font_kit::handle::Handle;
-use font_kit::properties::Properties;
+use font_kit::properties::{Properties, Style, Weight};
 use font_kit::source::SystemSource;
+use iced::Font;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use ttf_parser::Face;
 
 // Copyright 2020 The JetBrains Mono Project Authors (https://github.com/JetBrains/JetBrainsMono)
 //
@@ -98,7 +105,142 @@ use font_kit::source::SystemSource;
 // OTHER DEALINGS IN THE FONT SOFTWARE.
 pub const DEFAULT_FONT: &[u8] = include_bytes!("JetBrainsMonoNL-Regular.ttf");
 
+// Text fonts rarely have emoji glyphs, emoji fonts are tried after the configured families.
+// Bitmap color fonts such as "Noto Color Emoji" can't be rendered by iced.
+const EMOJI_FAMILIES: &str = "Noto Emoji, Symbola, Twemoji";
+
+pub fn load_emoji(families: Option<&str>) -> Option<&'static [u8]> {
+    match families {
+        Some(families) => load(&format!("{families}, {EMOJI_FAMILIES}")),
+        None => load(EMOJI_FAMILIES),
+    }
+}
+
+// The `--font-family` of the theme, or the bundled font
+pub static DEFAULT: Lazy<&'static [u8]> =
+    Lazy::new(|| THEME.font.as_deref().and_then(load).unwrap_or(DEFAULT_FONT));
+
+// Used when the font of an element lacks some glyphs of a text, e.g. CJK application names
+const FALLBACK_FAMILIES: &[&str] = &[
+    "Noto Sans CJK JP",
+    "Noto Sans CJK SC",
+    "Source Han Sans",
+    "WenQuanYi Micro Hei",
+    "Droid Sans Fallback",
+    "Noto Sans",
+    "DejaVu Sans",
+    "sans-serif",
+];
+
+// iced needs static font bytes, fonts are leaked so each one is loaded only once
+static LOADED: Lazy<Mutex<HashMap<OnagreFont, Option<LoadedFont>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// The parsed face is kept to look up glyphs on each view without parsing the font again
+#[derive(Debug, Clone, Copy)]
+struct LoadedFont {
+    font: Font,
+    face: Option<&'static Face<'static>>,
+}
+
+impl LoadedFont {
+    fn new(font: Font, bytes: &'static [u8]) -> Self {
+        let face = Face::parse(bytes, 0)
+            .ok()
+            .map(|face| &*Box::leak(Box::new(face)));
+        LoadedFont { font, face }
+    }
+}
+
+static DEFAULT_LOADED: Lazy<LoadedFont> = Lazy::new(|| LoadedFont::new(Font::Default, *DEFAULT));
+
+pub fn for_text(font: &OnagreFont, text: &str) -> Font {
+    let primary = resolve(font).unwrap_or(*DEFAULT_LOADED);
+
+    if covers(primary.face, text) {
+        return primary.font;
+    }
+
+    FALLBACK_FAMILIES
+        .iter()
+        .filter_map(|family| {
+            resolve(&OnagreFont {
+                family: Some(family.to_string()),
+                ..font.clone()
+            })
+        })
+        .find(|fallback| covers(fallback.face, text))
+        .unwrap_or(primary)
+        .font
+}
+
+fn resolve(font: &OnagreFont) -> Option<LoadedFont> {
+    if font.is_default() {
+        return Some(*DEFAULT_LOADED);
+    }
+
+    let mut loaded = LOADED.lock().unwrap();
+    *loaded.entry(font.clone()).or_insert_with(|| {
+        let family = match &font.family {
+            Some(family) => family.clone(),
+            None => format!(
+                "{}, monospace",
+                THEME.font.as_deref().unwrap_or("JetBrains Mono")
+            ),
+        };
+
+        let properties = Properties {
+            weight: Weight(f32::from(font.weight)),
+            style: match font.style {
+                FontStyle::Normal => Style::Normal,
+                FontStyle::Italic => Style::Italic,
+                FontStyle::Oblique => Style::Oblique,
+            },
+            ..Properties::default()
+        };
+
+        let bytes = load_with(&family, &properties)?;
+        // iced identifies external fonts by name
+        let name = format!("{family}:{}:{:?}", font.weight, font.style);
+        let font = Font::External {
+            name: Box::leak(name.into_boxed_str()),
+            bytes,
+        };
+        Some(LoadedFont::new(font, bytes))
+    })
+}
+
+// Whether the font has a glyph for every visible character of `text`
+fn covers(face: Option<&Face>, text: &str) -> bool {
+    match face {
+        Some(face) => text
+            .chars()
+            .filter(|c| !c.is_whitespace() && !c.is_control())
+            .all(|c| face.glyph_index(c).is_some()),
+        // Not our business if the font can't be parsed
+        None => true,
+    }
+}
+
+// Height of a line of text as laid out by iced: glyph_brush scales the font
+// so that `ascender - descender` spans `size`, then adds the line gap
+pub fn line_height(font: &OnagreFont, size: f32) -> f32 {
+    let face = resolve(font).unwrap_or(*DEFAULT_LOADED).face;
+    let ratio = face
+        .and_then(|face| {
+            let height = f32::from(face.ascender()) - f32::from(face.descender());
+            (height > 0.0).then(|| (height + f32::from(face.line_gap())) / height)
+        })
+        .unwrap_or(1.3);
+
+    (size * ratio).ceil()
+}
+
 pub fn load(name: &str) -> Option<&'static [u8]> {
+    load_with(name, &Properties::default())
+}
+
+fn load_with(name: &str, properties: &Properties) -> Option<&'static [u8]> {
     let mut families = Vec::new();
     for family in name.split(',') {
         let family = family.replace('\'', "");
@@ -113,20 +255,17 @@ pub fn load(name: &str) -> Option<&'static [u8]> {
         });
     }
 
-    let properties = Properties::default();
     let source = SystemSource::new();
-    let handle = source.select_best_match(&families, &properties);
+    let handle = source.select_best_match(&families, properties);
     match handle {
-        Ok(handle) => match handle {
-            Handle::Path { ref path, .. } => {
-                let contents = std::fs::read(path).unwrap();
-                let contents = Box::new(contents);
-                let contents = Box::leak(contents);
-
-                Some(contents.as_slice())
-            }
-            _ => None,
-        },
+        Ok(handle) => {
+            let contents = match handle {
+                Handle::Path { ref path, .. } => std::fs::read(path).ok()?,
+                Handle::Memory { bytes, .. } => bytes.to_vec(),
+            };
+
+            Some(Box::leak(contents.into_boxed_slice()))
+        }
         Err(err) => {
             eprintln!("Failed to load fond {name}: {err}");
             None
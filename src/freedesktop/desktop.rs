@@ -2,6 +2,11 @@ use serde::Deserialize;
 use std::borrow::Cow;
 use std::path::Path;
 
+// Environment variables used by mesa (PRIME) and the nvidia proprietary driver
+// to offload rendering to the discrete GPU
+pub const DISCRETE_GPU_ENV: [(&str, &str); 2] =
+    [("DRI_PRIME", "1"), ("__NV_PRIME_RENDER_OFFLOAD", "1")];
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct DesktopEntryIni<'a> {
@@ -18,6 +23,8 @@ pub struct DesktopEntry<'a> {
     pub actions: Option<Cow<'a, str>>,
     pub comment: Option<Cow<'a, str>>,
     pub keywords: Option<Cow<'a, str>>,
+    #[serde(rename = "PrefersNonDefaultGPU")]
+    pub prefers_non_default_gpu: Option<Cow<'a, str>>,
 }
 
 impl DesktopEntry<'_> {
@@ -29,4 +36,39 @@ impl DesktopEntry<'_> {
             .flatten()
             .map(|ini| ini.content)
     }
+
+    pub fn prefers_non_default_gpu(&self) -> bool {
+        self.prefers_non_default_gpu
+            .as_deref()
+            .map(|value| value.trim() == "true")
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::freedesktop::desktop::DesktopEntryIni;
+    use speculoos::prelude::*;
+
+    #[test]
+    fn should_read_non_default_gpu_preference() {
+        let content = "[Desktop Entry]\nName=Steam\nExec=steam %U\nPrefersNonDefaultGPU=true\n";
+
+        let entry = serde_ini::from_str::<DesktopEntryIni>(content)
+            .unwrap()
+            .content;
+
+        assert_that!(entry.prefers_non_default_gpu()).is_true();
+    }
+
+    #[test]
+    fn should_default_to_integrated_gpu() {
+        let content = "[Desktop Entry]\nName=Firefox\nExec=firefox %u\n";
+
+        let entry = serde_ini::from_str::<DesktopEntryIni>(content)
+            .unwrap()
+            .content;
+
+        assert_that!(entry.prefers_non_default_gpu()).is_false();
+    }
 }
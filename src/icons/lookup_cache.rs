@@ -0,0 +1,269 @@
+use crate::icons::theme::icon_dirs;
+use crate::icons::{pipeline, IconPath};
+use crate::THEME;
+use log::warn;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, UNIX_EPOCH};
+
+// Walking icon theme directories is the slowest part of a cold start,
+// resolved paths are kept on disk until an icon theme directory changes.
+static CACHE: Lazy<Mutex<LookupCache>> = Lazy::new(|| {
+    let stamp = stamp(&icon_dirs());
+    let cache = CACHE_FILE
+        .as_deref()
+        .map(|file| LookupCache::load(file, stamp))
+        .unwrap_or_else(|| LookupCache::new(stamp));
+
+    Mutex::new(cache)
+});
+
+static CACHE_FILE: Lazy<Option<PathBuf>> =
+    Lazy::new(|| dirs::cache_dir().map(|cache| cache.join("onagre").join("icon-lookup.json")));
+
+// Lookups made while rendering are saved together
+static SAVE_SCHEDULED: AtomicBool = AtomicBool::new(false);
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LookupKey {
+    pub name: String,
+    pub theme: String,
+    pub size: u16,
+    pub scale: u16,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    key: LookupKey,
+    path: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    stamp: u64,
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug, Default)]
+pub struct LookupCache {
+    stamp: u64,
+    // Result of the theme lookup, `None` when the icon does not exist
+    paths: HashMap<LookupKey, Option<PathBuf>>,
+    icons: HashMap<LookupKey, Option<IconPath>>,
+}
+
+impl LookupCache {
+    fn new(stamp: u64) -> Self {
+        LookupCache {
+            stamp,
+            ..Default::default()
+        }
+    }
+
+    // Entries saved with a different stamp are discarded
+    pub fn load(file: &Path, stamp: u64) -> Self {
+        let saved: Option<CacheFile> = fs::read(file)
+            .ok()
+            .and_then(|content| serde_json::from_slice(&content).ok());
+
+        match saved {
+            Some(saved) if saved.stamp == stamp => LookupCache {
+                stamp,
+                paths: saved
+                    .entries
+                    .into_iter()
+                    .map(|entry| (entry.key, entry.path))
+                    .collect(),
+                icons: HashMap::new(),
+            },
+            _ => LookupCache::new(stamp),
+        }
+    }
+
+    pub fn save(&self, file: &Path) -> anyhow::Result<()> {
+        let content = CacheFile {
+            stamp: self.stamp,
+            entries: self
+                .paths
+                .iter()
+                .map(|(key, path)| Entry {
+                    key: key.clone(),
+                    path: path.clone(),
+                })
+                .collect(),
+        };
+
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let tmp = file.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec(&content)?)?;
+        fs::rename(tmp, file)?;
+        Ok(())
+    }
+
+    // Returns the cached icon, or resolves its path with `find` and caches it.
+    // The second value is true when `find` was called.
+    pub fn get_or_find<F>(&mut self, key: &LookupKey, find: F) -> (Option<IconPath>, bool)
+    where
+        F: FnOnce() -> Option<PathBuf>,
+    {
+        if let Some(icon) = self.icons.get(key) {
+            return (icon.clone(), false);
+        }
+
+        let (path, found) = match self.paths.get(key) {
+            // The icon might have been removed without touching the theme directory
+            Some(Some(path)) if !path.exists() => (find(), true),
+            Some(path) => (path.clone(), false),
+            None => (find(), true),
+        };
+
+        if found {
+            self.paths.insert(key.clone(), path.clone());
+        }
+
+        let symbolic = key.name.ends_with("-symbolic");
+        let icon = path
+            .as_ref()
+            .and_then(|path| IconPath::from_path(path, symbolic));
+
+        // Icons converted in the background are not ready yet, they are looked up again
+        let pending = matches!(&path, Some(path) if pipeline::is_pending(path, THEME.icon_size));
+        if (icon.is_some() || path.is_none()) && !pending {
+            self.icons.insert(key.clone(), icon.clone());
+        }
+
+        (icon, found)
+    }
+}
+
+pub fn lookup<F>(key: LookupKey, find: F) -> Option<IconPath>
+where
+    F: FnOnce() -> Option<PathBuf>,
+{
+    let (icon, found) = CACHE.lock().unwrap().get_or_find(&key, find);
+    if found {
+        schedule_save();
+    }
+
+    icon
+}
+
+fn schedule_save() {
+    let file = match CACHE_FILE.as_ref() {
+        Some(file) => file,
+        None => return,
+    };
+
+    if SAVE_SCHEDULED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    thread::spawn(move || {
+        thread::sleep(SAVE_DELAY);
+        SAVE_SCHEDULED.store(false, Ordering::SeqCst);
+        if let Err(err) = CACHE.lock().unwrap().save(file) {
+            warn!("Failed to save the icon lookup cache: {err}");
+        }
+    });
+}
+
+// Changes when a theme is installed or removed, or when an icon theme cache is updated
+pub fn stamp(dirs: &[PathBuf]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for dir in dirs {
+        hash_mtime(dir, &mut hasher);
+        let mut themes: Vec<PathBuf> = fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect();
+
+        themes.sort();
+        for theme in themes {
+            hash_mtime(&theme, &mut hasher);
+        }
+    }
+
+    hasher.finish()
+}
+
+fn hash_mtime(path: &Path, hasher: &mut DefaultHasher) {
+    let modified = fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok());
+
+    path.hash(hasher);
+    modified.hash(hasher);
+}
+
+#[cfg(test)]
+mod test {
+    use crate::fixtures::fixture_dir;
+    use crate::icons::lookup_cache::{stamp, LookupCache, LookupKey};
+    use speculoos::prelude::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn key(name: &str) -> LookupKey {
+        LookupKey {
+            name: name.to_string(),
+            theme: "Papirus".to_string(),
+            size: 32,
+            scale: 1,
+        }
+    }
+
+    #[test]
+    fn should_reuse_saved_lookups_until_stamp_changes() {
+        let dir = fixture_dir("icon-lookup-cache");
+        let icon = dir.join("firefox.svg");
+        fs::write(&icon, "<svg/>").unwrap();
+        let file = dir.join("icon-lookup.json");
+
+        let mut cache = LookupCache::load(&file, 1);
+        let (found, searched) = cache.get_or_find(&key("firefox"), || Some(icon.clone()));
+        assert_that!(searched).is_true();
+        assert_that!(found.map(|icon| icon.path)).is_equal_to(Some(icon.clone()));
+        cache.get_or_find(&key("missing"), || None);
+        cache.save(&file).unwrap();
+
+        let mut cache = LookupCache::load(&file, 1);
+        let (found, searched) = cache.get_or_find(&key("firefox"), || unreachable!());
+        assert_that!(searched).is_false();
+        assert_that!(found.map(|icon| icon.path)).is_equal_to(Some(icon));
+        let (missing, searched) = cache.get_or_find(&key("missing"), || unreachable!());
+        assert_that!(searched).is_false();
+        assert_that!(missing).is_equal_to(None);
+
+        let mut cache = LookupCache::load(&file, 2);
+        let (_, searched) = cache.get_or_find(&key("firefox"), || None);
+        assert_that!(searched).is_true();
+    }
+
+    #[test]
+    fn should_change_stamp_when_a_theme_is_added() {
+        let dir = fixture_dir("icon-lookup-stamp");
+        let dirs = vec![dir.clone(), PathBuf::from("/does/not/exist")];
+
+        let before = stamp(&dirs);
+        assert_that!(stamp(&dirs)).is_equal_to(before);
+
+        fs::create_dir_all(dir.join("Papirus")).unwrap();
+        assert_that!(stamp(&dirs) != before).is_true();
+    }
+}
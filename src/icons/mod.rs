@@ -5,7 +5,9 @@ use std::convert::TryFrom;
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
+use crate::app::scale;
 use crate::config::color::OnagreColor;
+use crate::icons::lookup_cache::LookupKey;
 use crate::THEME;
 use anyhow::anyhow;
 use iced::Renderer;
@@ -13,8 +15,14 @@ use once_cell::sync::Lazy;
 use pop_launcher_toolkit::launcher::IconSource;
 use serde::{Deserialize, Serialize};
 
+pub mod lookup_cache;
+pub mod pipeline;
+mod symbolic;
+pub mod theme;
+mod xpm;
+
 // We use this only for symbolic svg icons which needs to be loaded with a color theme
-// For other icons, the freedesktop-icon crate has a cache already
+// Other icons paths are cached in `lookup_cache`
 pub(crate) static SYMBOLIC_ICON_CACHE: Lazy<Mutex<HashMap<String, Vec<u8>>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
@@ -41,7 +49,8 @@ pub fn fallback_icon(color: &OnagreColor) -> Svg<Renderer> {
     let svg = match cache.get(&key) {
         Some(svg) => svg,
         None => {
-            let svg = inject_color_into_svg(FALLBACK_ICON, &hex_color);
+            let svg = symbolic::recolor(FALLBACK_ICON, (*color).into())
+                .unwrap_or_else(|_| FALLBACK_ICON.to_string());
             cache.insert(key.clone(), svg.into_bytes());
             cache.get(&key).unwrap()
         }
@@ -52,18 +61,6 @@ pub fn fallback_icon(color: &OnagreColor) -> Svg<Renderer> {
     Svg::new(handle)
 }
 
-fn inject_color_into_svg(content: &str, hex_color: &str) -> String {
-    // Svg does not support transparency for hex colors
-    let hex_color = &hex_color[0..7];
-
-    let mut string = content.to_string();
-    for (pos, _) in content.match_indices('#') {
-        string.replace_range(pos..pos + 7, hex_color);
-    }
-
-    string
-}
-
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IconPath {
     pub path: PathBuf,
@@ -78,22 +75,29 @@ impl AsRef<IconPath> for IconPath {
 }
 
 impl IconPath {
-    pub fn lookup(name: &str, theme: &str, size: u16) -> Option<Self> {
-        if name.ends_with("-symbolic") {
-            freedesktop_icons::lookup(name)
-                .with_theme(theme)
-                .with_cache()
-                .force_svg()
-                .find()
-                .and_then(|icon| Self::from_path(icon, true))
-        } else {
-            freedesktop_icons::lookup(name)
-                .with_scale(1)
-                .with_theme(theme)
-                .with_size(size)
-                .find()
-                .and_then(|icon| Self::from_path(icon, false))
-        }
+    pub fn lookup(name: &str, themes: &[String], size: u16) -> Option<Self> {
+        let scale = scale::icon_scale();
+        let key = LookupKey {
+            name: name.to_string(),
+            theme: themes.join(","),
+            size,
+            scale,
+        };
+
+        lookup_cache::lookup(key, || Self::find(name, themes, size, scale))
+    }
+
+    // Search the icon theme directories, without caching.
+    // `scale` selects the `@2x` directories of themes providing them
+    pub fn find(name: &str, themes: &[String], size: u16, scale: u16) -> Option<PathBuf> {
+        theme::find(name, themes, size, scale)
+    }
+
+    // The icon configured with `--fallback-icons` for this type of entry
+    pub fn fallback(entry_type: &str) -> Option<Self> {
+        let themes = THEME.icon_theme.as_ref()?;
+        let name = THEME.fallback_icons.get(entry_type)?;
+        IconPath::lookup(name, themes, THEME.icon_size)
     }
 
     pub fn absolute_from_icon_source(source: Option<&IconSource>) -> Option<Self> {
@@ -107,26 +111,37 @@ impl IconPath {
         })
     }
 
-    pub fn from_source(source: &IconSource, theme: &str) -> Option<Self> {
+    pub fn from_source(source: &IconSource, themes: &[String]) -> Option<Self> {
         match source {
-            IconSource::Name(name) => IconPath::lookup(name, theme, THEME.icon_size),
+            IconSource::Name(name) => IconPath::lookup(name, themes, THEME.icon_size),
             IconSource::Mime(mime) => {
                 let name = mime.replace('/', "-");
-                IconPath::lookup(&name, theme, THEME.icon_size)
+                IconPath::lookup(&name, themes, THEME.icon_size)
             }
         }
     }
 
     pub fn from_path<P: AsRef<Path>>(path: P, symbolic: bool) -> Option<Self> {
-        path.as_ref()
-            .extension()
-            .map(|ext| ext.to_string_lossy())
-            .and_then(|ext| Extension::try_from(ext.as_ref()).ok())
-            .map(|extension| Self {
-                path: path.as_ref().to_path_buf(),
-                extension,
-                symbolic,
-            })
+        let path = path.as_ref();
+        match path.extension()?.to_string_lossy().as_ref() {
+            // Formats iced can't render are converted in the background
+            "ico" | "xpm" => pipeline::converted(path, THEME.icon_size),
+            ext => {
+                let icon = Extension::try_from(ext).ok().map(|extension| Self {
+                    path: path.to_path_buf(),
+                    extension,
+                    symbolic,
+                })?;
+
+                // Symbolic icons are recolored when rendered. Others are cached as png at the
+                // displayed size, the original is rendered until the conversion is done.
+                if symbolic {
+                    Some(icon)
+                } else {
+                    pipeline::converted(path, THEME.icon_size).or(Some(icon))
+                }
+            }
+        }
     }
 
     // If we have a symbolic icon try to replace the foreground color with the current
@@ -137,13 +152,20 @@ impl IconPath {
             let hex_color = color.to_string();
             let key = format!("{hex_color}{:?}", self.path);
             let svg = match icon_cache.get(&key) {
-                None => {
-                    let content =
-                        std::fs::read_to_string(&self.path).expect("Icon path does not exists");
-                    let svg = inject_color_into_svg(&content, &hex_color);
-                    icon_cache.insert(key.clone(), svg.as_bytes().to_vec());
-                    icon_cache.get(&key).unwrap()
-                }
+                None => match std::fs::read_to_string(&self.path)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|content| symbolic::recolor(&content, (*color).into()))
+                {
+                    Ok(svg) => {
+                        icon_cache.insert(key.clone(), svg.into_bytes());
+                        icon_cache.get(&key).unwrap()
+                    }
+                    // The icon was removed, is unreadable or is not a valid svg
+                    Err(_) => {
+                        drop(icon_cache);
+                        return fallback_icon(color);
+                    }
+                },
                 Some(svg) => svg,
             };
 
@@ -0,0 +1,277 @@
+use crate::app::scale;
+use crate::icons::{xpm, Extension, IconPath};
+use anyhow::{anyhow, Result};
+use iced::futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use image::imageops::FilterType;
+use image::{ImageFormat, RgbaImage};
+use log::error;
+use once_cell::sync::Lazy;
+use resvg::{tiny_skia, usvg};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::Mutex;
+use std::thread;
+
+// Icons iced can't render directly (ico, xpm, favicons of unknown format) and theme icons
+// are converted to png at the displayed size in a background thread and stored in onagre's
+// cache. Until then `converted` returns `None` and rows display the fallback or original icon.
+static JOBS: Lazy<Mutex<HashMap<(PathBuf, u16), Job>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+// Notifies the UI that an icon is ready so the rows are redrawn
+static READY: Lazy<Mutex<Option<UnboundedSender<()>>>> = Lazy::new(|| Mutex::new(None));
+
+// Conversions are queued to a single worker thread, a screen of favicons doesn't start
+// a thread per icon
+static QUEUE: Lazy<Mutex<Sender<(PathBuf, u16, PathBuf)>>> = Lazy::new(|| {
+    let (sender, receiver) = channel::<(PathBuf, u16, PathBuf)>();
+    thread::spawn(move || {
+        for (source, size, dest) in receiver {
+            run(source, size, dest);
+        }
+    });
+
+    Mutex::new(sender)
+});
+
+static CACHE_DIR: Lazy<Option<PathBuf>> =
+    Lazy::new(|| dirs::cache_dir().map(|cache| cache.join("onagre").join("icons")));
+
+#[derive(Debug, Clone)]
+enum Job {
+    Pending,
+    Ready(PathBuf),
+    Failed,
+}
+
+// The png rendering of `source` at `size` logical pixels, if the conversion is done
+pub fn converted(source: &Path, size: u16) -> Option<IconPath> {
+    let size = scale::icon_pixels(size);
+    let key = (source.to_path_buf(), size);
+    let mut jobs = JOBS.lock().unwrap();
+    let job = match jobs.get(&key) {
+        Some(job) => job.clone(),
+        None => {
+            let dest = cache_path(CACHE_DIR.as_ref()?, source, size);
+            let job = if is_fresh(source, &dest) {
+                Job::Ready(dest)
+            } else {
+                enqueue(source.to_path_buf(), size, dest);
+                Job::Pending
+            };
+
+            jobs.insert(key, job.clone());
+            job
+        }
+    };
+
+    match job {
+        Job::Ready(path) => Some(IconPath {
+            path,
+            extension: Extension::Png,
+            symbolic: false,
+        }),
+        Job::Pending | Job::Failed => None,
+    }
+}
+
+// True while `source` is being converted to `size` logical pixels
+pub fn is_pending(source: &Path, size: u16) -> bool {
+    let key = (source.to_path_buf(), scale::icon_pixels(size));
+    matches!(JOBS.lock().unwrap().get(&key), Some(Job::Pending))
+}
+
+// Receive a message each time an icon conversion completes
+pub fn ready_notifications() -> UnboundedReceiver<()> {
+    let (sender, receiver) = mpsc::unbounded();
+    *READY.lock().unwrap() = Some(sender);
+    receiver
+}
+
+fn enqueue(source: PathBuf, size: u16, dest: PathBuf) {
+    let _ = QUEUE.lock().unwrap().send((source, size, dest));
+}
+
+fn run(source: PathBuf, size: u16, dest: PathBuf) {
+    let job = match convert(&source, size, &dest) {
+        Ok(()) => Job::Ready(dest),
+        Err(err) => {
+            error!("Failed to convert icon {source:?}: {err}");
+            Job::Failed
+        }
+    };
+
+    JOBS.lock().unwrap().insert((source, size), job);
+    if let Some(sender) = READY.lock().unwrap().as_ref() {
+        let _ = sender.unbounded_send(());
+    }
+}
+
+fn convert(source: &Path, size: u16, dest: &Path) -> Result<()> {
+    let data = fs::read(source)?;
+    let image = decode(&data, u32::from(size))?;
+    let image = fit(image, u32::from(size));
+
+    // Write to a temporary file first so a partially written icon is never displayed
+    let parent = dest.parent().ok_or_else(|| anyhow!("Invalid cache path"))?;
+    fs::create_dir_all(parent)?;
+    let tmp = dest.with_extension("tmp");
+    image.save_with_format(&tmp, ImageFormat::Png)?;
+    fs::rename(tmp, dest)?;
+    Ok(())
+}
+
+// The format is guessed from the content, favicons are often saved with the wrong extension
+pub fn decode(data: &[u8], size: u32) -> Result<RgbaImage> {
+    if data.starts_with(&[0, 0, 1, 0]) {
+        decode_ico(data, size)
+    } else if data.starts_with(b"\x89PNG") {
+        Ok(image::load_from_memory_with_format(data, ImageFormat::Png)?.to_rgba8())
+    } else {
+        let text = String::from_utf8_lossy(data);
+        if text.contains("XPM") || text.trim_start().starts_with("static char") {
+            xpm::decode(&text)
+        } else if text.contains("<svg") {
+            decode_svg(data, size)
+        } else {
+            Err(anyhow!("Unsupported icon format"))
+        }
+    }
+}
+
+// Use the smallest image at least as large as the requested size, or the largest one
+fn decode_ico(data: &[u8], size: u32) -> Result<RgbaImage> {
+    let icon = ico::IconDir::read(Cursor::new(data))?;
+    let mut entries: Vec<_> = icon.entries().iter().collect();
+    entries.sort_by_key(|entry| entry.width());
+    let entry = entries
+        .iter()
+        .find(|entry| entry.width() >= size)
+        .or_else(|| entries.last())
+        .ok_or_else(|| anyhow!("Empty ico file"))?;
+
+    let image = entry.decode()?;
+    RgbaImage::from_raw(image.width(), image.height(), image.rgba_data().to_vec())
+        .ok_or_else(|| anyhow!("Invalid ico image"))
+}
+
+fn decode_svg(data: &[u8], size: u32) -> Result<RgbaImage> {
+    let tree = usvg::Tree::from_data(data, &usvg::Options::default())?;
+    let scale = f64::from(size) / tree.size.width().max(tree.size.height());
+    let width = (tree.size.width() * scale).ceil().max(1.0) as u32;
+    let height = (tree.size.height() * scale).ceil().max(1.0) as u32;
+    let mut pixmap =
+        tiny_skia::Pixmap::new(width, height).ok_or_else(|| anyhow!("Invalid svg size"))?;
+
+    resvg::render(
+        &tree,
+        usvg::FitTo::Size(width, height),
+        tiny_skia::Transform::default(),
+        pixmap.as_mut(),
+    )
+    .ok_or_else(|| anyhow!("Failed to render svg"))?;
+
+    // tiny-skia pixels are premultiplied
+    let pixels = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+
+    RgbaImage::from_raw(width, height, pixels).ok_or_else(|| anyhow!("Invalid svg image"))
+}
+
+// Scale the image to fit a `size` square, keeping its aspect ratio
+fn fit(image: RgbaImage, size: u32) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    if width.max(height) == size || width == 0 || height == 0 {
+        return image;
+    }
+
+    let scale = size as f32 / width.max(height) as f32;
+    let width = ((width as f32 * scale).round() as u32).max(1);
+    let height = ((height as f32 * scale).round() as u32).max(1);
+    image::imageops::resize(&image, width, height, FilterType::Lanczos3)
+}
+
+fn cache_path(cache_dir: &Path, source: &Path, size: u16) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    cache_dir.join(format!("{:x}-{size}.png", hasher.finish()))
+}
+
+// A cached icon is reused unless the source changed since it was converted
+fn is_fresh(source: &Path, dest: &Path) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|meta| meta.modified());
+    match (modified(source), modified(dest)) {
+        (Ok(source), Ok(dest)) => dest >= source,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::fixtures::fixture_dir;
+    use crate::icons::pipeline::{convert, decode, fit};
+    use image::{ImageFormat, Rgba, RgbaImage};
+    use speculoos::prelude::*;
+    use std::fs;
+
+    const SVG: &[u8] = br##"<svg xmlns="http://www.w3.org/2000/svg" width="16" height="8">
+        <rect width="16" height="8" fill="#ff0000"/>
+    </svg>"##;
+
+    #[test]
+    fn should_decode_ico_and_png() {
+        let mut png = vec![];
+        RgbaImage::from_pixel(4, 4, Rgba([0, 255, 0, 255]))
+            .write_to(&mut std::io::Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+
+        let mut icon_dir = ico::IconDir::new(ico::ResourceType::Icon);
+        let image = ico::IconImage::from_rgba_data(8, 8, vec![255; 8 * 8 * 4]);
+        icon_dir.add_entry(ico::IconDirEntry::encode(&image).unwrap());
+        let mut ico = vec![];
+        icon_dir.write(&mut ico).unwrap();
+
+        assert_that!(decode(&png, 32).unwrap().dimensions()).is_equal_to((4, 4));
+        assert_that!(decode(&ico, 32).unwrap().dimensions()).is_equal_to((8, 8));
+    }
+
+    #[test]
+    fn should_render_svg_at_requested_size() {
+        let image = decode(SVG, 32).unwrap();
+
+        assert_that!(image.dimensions()).is_equal_to((32, 16));
+        assert_that!(*image.get_pixel(0, 0)).is_equal_to(Rgba([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn should_fit_image_keeping_aspect_ratio() {
+        let image = fit(RgbaImage::new(64, 32), 16);
+
+        assert_that!(image.dimensions()).is_equal_to((16, 8));
+    }
+
+    #[test]
+    fn should_not_panic_on_unreadable_icons() {
+        let dir = fixture_dir("icon-pipeline");
+        let garbage = dir.join("garbage.ico");
+        fs::write(&garbage, b"not an icon").unwrap();
+
+        assert_that!(convert(&garbage, 32, &dir.join("garbage.png"))).is_err();
+        assert_that!(convert(
+            &dir.join("missing.ico"),
+            32,
+            &dir.join("missing.png")
+        ))
+        .is_err();
+    }
+}
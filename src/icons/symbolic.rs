@@ -0,0 +1,394 @@
+use anyhow::Result;
+use iced::Color;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, Writer};
+
+// Symbolic icons follow the GTK convention: shapes are painted with the foreground color
+// unless they have a `success`, `warning` or `error` class. `<role>-fill` and `<role>-stroke`
+// classes only recolor the fill or the stroke. Other colors used in the icon are ignored.
+const SHAPES: &[&str] = &[
+    "path", "rect", "circle", "ellipse", "line", "polyline", "polygon", "text",
+];
+
+// Their paint is inherited by the shapes they contain
+const CONTAINERS: &[&str] = &["svg", "g", "a", "use", "symbol"];
+
+// Content used for clipping and masking, recoloring it would change the icon shape
+const NOT_PAINTED: &[&str] = &["defs", "mask", "clipPath"];
+
+// GTK fallback colors when the icon theme doesn't define them
+const SUCCESS: Color = Color {
+    r: 0.30588236,
+    g: 0.6039216,
+    b: 0.023529412,
+    a: 1.0,
+};
+
+const WARNING: Color = Color {
+    r: 0.9607843,
+    g: 0.4745098,
+    b: 0.0,
+    a: 1.0,
+};
+
+const ERROR: Color = Color {
+    r: 0.8,
+    g: 0.0,
+    b: 0.0,
+    a: 1.0,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Role {
+    Foreground,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Role {
+    fn from_name(name: &str) -> Option<Role> {
+        match name {
+            "foreground" => Some(Role::Foreground),
+            "success" => Some(Role::Success),
+            "warning" => Some(Role::Warning),
+            "error" => Some(Role::Error),
+            _ => None,
+        }
+    }
+
+    fn color(self, foreground: Color) -> Color {
+        match self {
+            Role::Foreground => foreground,
+            Role::Success => SUCCESS,
+            Role::Warning => WARNING,
+            Role::Error => ERROR,
+        }
+    }
+}
+
+// Paint state inherited from the parent elements
+#[derive(Debug, Clone, Copy)]
+struct Context {
+    painted: bool,
+    has_fill: bool,
+    // Opacities as written in the icon, before applying the color alpha
+    fill_opacity: f32,
+    stroke_opacity: f32,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Context {
+            painted: true,
+            has_fill: false,
+            fill_opacity: 1.0,
+            stroke_opacity: 1.0,
+        }
+    }
+}
+
+// Replace the colors of a symbolic svg icon, the color alpha is applied
+// with `fill-opacity` and `stroke-opacity` since svg hex colors are opaque
+pub fn recolor(svg: &str, foreground: Color) -> Result<String> {
+    let mut reader = Reader::from_str(svg);
+    let mut writer = Writer::new(Vec::new());
+    let mut parents = vec![Context::default()];
+
+    loop {
+        let parent = parents.last().copied().unwrap_or_default();
+        match reader.read_event()? {
+            Event::Start(element) => {
+                let (element, context) = recolor_element(&element, parent, foreground)?;
+                parents.push(context);
+                writer.write_event(Event::Start(element))?;
+            }
+            Event::Empty(element) => {
+                let (element, _) = recolor_element(&element, parent, foreground)?;
+                writer.write_event(Event::Empty(element))?;
+            }
+            Event::End(element) => {
+                parents.pop();
+                writer.write_event(Event::End(element))?;
+            }
+            Event::Eof => break,
+            event => writer.write_event(event)?,
+        }
+    }
+
+    Ok(String::from_utf8(writer.into_inner())?)
+}
+
+fn recolor_element(
+    element: &BytesStart,
+    parent: Context,
+    foreground: Color,
+) -> Result<(BytesStart<'static>, Context)> {
+    let name = String::from_utf8_lossy(element.name().as_ref()).to_string();
+    let local_name = String::from_utf8_lossy(element.local_name().as_ref()).to_string();
+
+    let mut attributes = vec![];
+    for attribute in element.attributes() {
+        let attribute = attribute?;
+        let key = String::from_utf8_lossy(attribute.key.as_ref()).to_string();
+        attributes.push((key, attribute.unescape_value()?.to_string()));
+    }
+
+    let is_shape = SHAPES.contains(&local_name.as_str());
+    let mut context = parent;
+    if NOT_PAINTED.contains(&local_name.as_str()) {
+        context.painted = false;
+    }
+
+    if !context.painted || !(is_shape || CONTAINERS.contains(&local_name.as_str())) {
+        let mut unchanged = BytesStart::new(name);
+        unchanged.extend_attributes(
+            attributes
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str())),
+        );
+        return Ok((unchanged, context));
+    }
+
+    // The style attribute takes precedence over presentation attributes
+    let mut declarations = attributes
+        .iter()
+        .find(|(key, _)| key == "style")
+        .map(|(_, style)| parse_style(style))
+        .unwrap_or_default();
+
+    let (fill_role, stroke_role) = roles(&attributes);
+    let fill = fill_role.color(foreground);
+    let stroke = stroke_role.color(foreground);
+
+    let paint = |property: &str| {
+        find(&declarations, property)
+            .or_else(|| find(&attributes, property))
+            .map(str::to_string)
+    };
+
+    let own_fill = paint("fill");
+    let own_stroke = paint("stroke");
+    let fill_opacity = paint("fill-opacity").and_then(|value| value.trim().parse::<f32>().ok());
+    let stroke_opacity = paint("stroke-opacity").and_then(|value| value.trim().parse::<f32>().ok());
+
+    // Shapes without fill are painted black by default
+    let default_fill = is_shape && own_fill.is_none() && !parent.has_fill;
+    let fill_replaced =
+        default_fill || matches!(own_fill.as_deref(), Some(paint) if is_color(paint));
+    let stroke_replaced = matches!(own_stroke.as_deref(), Some(paint) if is_color(paint));
+
+    context.has_fill = parent.has_fill || own_fill.is_some();
+    context.fill_opacity = fill_opacity.unwrap_or(parent.fill_opacity);
+    context.stroke_opacity = stroke_opacity.unwrap_or(parent.stroke_opacity);
+
+    for properties in [&mut attributes, &mut declarations].iter_mut() {
+        for (key, value) in properties.iter_mut() {
+            match key.as_str() {
+                "fill" if is_color(value) => *value = to_hex(fill),
+                "stroke" if is_color(value) => *value = to_hex(stroke),
+                "fill-opacity" if fill_replaced => {
+                    *value = format_opacity(context.fill_opacity * fill.a)
+                }
+                "stroke-opacity" if stroke_replaced => {
+                    *value = format_opacity(context.stroke_opacity * stroke.a)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if default_fill {
+        attributes.push(("fill".to_string(), to_hex(fill)));
+    }
+
+    if fill_replaced && fill_opacity.is_none() && fill.a < 1.0 {
+        let opacity = format_opacity(context.fill_opacity * fill.a);
+        attributes.push(("fill-opacity".to_string(), opacity));
+    }
+
+    if stroke_replaced && stroke_opacity.is_none() && stroke.a < 1.0 {
+        let opacity = format_opacity(context.stroke_opacity * stroke.a);
+        attributes.push(("stroke-opacity".to_string(), opacity));
+    }
+
+    let style = format_style(&declarations);
+    let mut recolored = BytesStart::new(name);
+    recolored.extend_attributes(attributes.iter().map(|(key, value)| {
+        if key == "style" {
+            (key.as_str(), style.as_str())
+        } else {
+            (key.as_str(), value.as_str())
+        }
+    }));
+
+    Ok((recolored, context))
+}
+
+// `success` recolors both fill and stroke, `success-fill` and `success-stroke` only one of them
+fn roles(attributes: &[(String, String)]) -> (Role, Role) {
+    let classes = find(attributes, "class").unwrap_or_default();
+    let mut fill = None;
+    let mut stroke = None;
+
+    for class in classes.split_whitespace() {
+        if let Some(role) = class.strip_suffix("-fill").and_then(Role::from_name) {
+            fill = Some(role);
+        } else if let Some(role) = class.strip_suffix("-stroke").and_then(Role::from_name) {
+            stroke = Some(role);
+        } else if let Some(role) = Role::from_name(class) {
+            fill = fill.or(Some(role));
+            stroke = stroke.or(Some(role));
+        }
+    }
+
+    let fill = fill.unwrap_or(Role::Foreground);
+    (fill, stroke.unwrap_or(fill))
+}
+
+fn find<'a>(properties: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    properties
+        .iter()
+        .rev()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+// `none`, `url(#gradient)` and inherited paints are left untouched
+fn is_color(paint: &str) -> bool {
+    let paint = paint.trim();
+    !(paint.is_empty() || paint == "none" || paint == "inherit" || paint.starts_with("url("))
+}
+
+fn parse_style(style: &str) -> Vec<(String, String)> {
+    style
+        .split(';')
+        .filter_map(|declaration| declaration.split_once(':'))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+fn format_style(declarations: &[(String, String)]) -> String {
+    declarations
+        .iter()
+        .map(|(key, value)| format!("{key}:{value}"))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+fn to_hex(color: Color) -> String {
+    let channel = |value: f32| (value * 255.0).round() as u8;
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        channel(color.r),
+        channel(color.g),
+        channel(color.b)
+    )
+}
+
+fn format_opacity(opacity: f32) -> String {
+    ((opacity * 1000.0).round() / 1000.0).to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::icons::symbolic::recolor;
+    use iced::Color;
+    use quick_xml::events::Event;
+    use quick_xml::Reader;
+    use speculoos::prelude::*;
+    use std::collections::HashMap;
+
+    const FOREGROUND: Color = Color {
+        r: 1.0,
+        g: 1.0,
+        b: 1.0,
+        a: 1.0,
+    };
+
+    const TRANSLUCENT: Color = Color {
+        r: 0.2,
+        g: 0.4,
+        b: 0.6,
+        a: 0.5,
+    };
+
+    // Paint attributes of every element having an id
+    fn paints(svg: &str) -> HashMap<String, HashMap<String, String>> {
+        let mut reader = Reader::from_str(svg);
+        let mut paints = HashMap::new();
+        loop {
+            match reader.read_event().unwrap() {
+                Event::Start(element) | Event::Empty(element) => {
+                    let mut attributes: HashMap<String, String> = element
+                        .attributes()
+                        .map(|attribute| {
+                            let attribute = attribute.unwrap();
+                            let key = String::from_utf8_lossy(attribute.key.as_ref()).to_string();
+                            (key, attribute.unescape_value().unwrap().to_string())
+                        })
+                        .collect();
+
+                    let style = attributes.remove("style").unwrap_or_default();
+                    for (key, value) in style.split(';').filter_map(|decl| decl.split_once(':')) {
+                        attributes.insert(format!("style:{key}"), value.to_string());
+                    }
+
+                    if let Some(id) = attributes.get("id").cloned() {
+                        paints.insert(id, attributes);
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        paints
+    }
+
+    fn attribute<'a>(
+        paints: &'a HashMap<String, HashMap<String, String>>,
+        id: &str,
+        key: &str,
+    ) -> Option<&'a str> {
+        paints[id].get(key).map(String::as_str)
+    }
+
+    #[test]
+    fn should_recolor_gtk_symbolic_classes() {
+        let svg = recolor(include_str!("fixtures/status-symbolic.svg"), FOREGROUND).unwrap();
+        let paints = paints(&svg);
+
+        assert_that!(attribute(&paints, "short-hex", "fill")).is_equal_to(Some("#ffffff"));
+        assert_that!(attribute(&paints, "no-fill", "fill")).is_equal_to(Some("#ffffff"));
+        assert_that!(attribute(&paints, "success", "fill")).is_equal_to(Some("#4e9a06"));
+        assert_that!(attribute(&paints, "warning", "style:fill")).is_equal_to(Some("#f57900"));
+        assert_that!(attribute(&paints, "error-stroke", "stroke")).is_equal_to(Some("#cc0000"));
+        assert_that!(attribute(&paints, "error-stroke", "fill")).is_equal_to(Some("none"));
+    }
+
+    #[test]
+    fn should_not_corrupt_references_and_ids() {
+        let svg = recolor(include_str!("fixtures/gradient-symbolic.svg"), FOREGROUND).unwrap();
+        let paints = paints(&svg);
+
+        assert_that!(attribute(&paints, "uses-gradient", "fill")).is_equal_to(Some("url(#fade)"));
+        assert_that!(attribute(&paints, "path#1", "fill")).is_equal_to(Some("#ffffff"));
+        assert_that!(attribute(&paints, "stop", "stop-color")).is_equal_to(Some("#123"));
+        assert_that!(attribute(&paints, "mask-content", "fill")).is_equal_to(Some("#fff"));
+        assert_that!(attribute(&paints, "in-group", "fill")).is_equal_to(None);
+        assert_that!(attribute(&paints, "group", "fill")).is_equal_to(Some("#ffffff"));
+    }
+
+    #[test]
+    fn should_preserve_alpha() {
+        let svg = recolor(include_str!("fixtures/status-symbolic.svg"), TRANSLUCENT).unwrap();
+        let paints = paints(&svg);
+
+        assert_that!(attribute(&paints, "short-hex", "fill")).is_equal_to(Some("#336699"));
+        assert_that!(attribute(&paints, "short-hex", "fill-opacity")).is_equal_to(Some("0.5"));
+        assert_that!(attribute(&paints, "half-opaque", "fill-opacity")).is_equal_to(Some("0.25"));
+        assert_that!(attribute(&paints, "success", "fill-opacity")).is_equal_to(None);
+        assert_that!(attribute(&paints, "half-opaque", "opacity")).is_equal_to(Some("0.8"));
+    }
+}
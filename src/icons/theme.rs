@@ -0,0 +1,390 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// Every icon theme ends up inheriting from hicolor
+const DEFAULT_THEME: &str = "hicolor";
+
+// Legacy location of unthemed icons
+const PIXMAPS: &str = "/usr/share/pixmaps";
+
+static THEMES: Lazy<Mutex<IconThemes>> = Lazy::new(|| Mutex::new(IconThemes::new(icon_dirs())));
+
+// Icon lookup following the freedesktop icon theme specification:
+// the configured themes and the themes they inherit from are searched in order,
+// then hicolor and finally the unthemed icons.
+pub fn find(name: &str, themes: &[String], size: u16, scale: u16) -> Option<PathBuf> {
+    THEMES.lock().unwrap().find(name, themes, size, scale)
+}
+
+// Icon base directories, see the freedesktop icon theme specification
+pub(crate) fn icon_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![];
+    dirs.extend(dirs::home_dir().map(|home| home.join(".icons")));
+    dirs.extend(dirs::data_dir().map(|data| data.join("icons")));
+
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .unwrap_or_else(|_| "/usr/local/share:/usr/share".to_string());
+    dirs.extend(
+        data_dirs
+            .split(':')
+            .filter(|dir| !dir.is_empty())
+            .map(|dir| Path::new(dir).join("icons")),
+    );
+
+    dirs.push(PathBuf::from(PIXMAPS));
+    dirs
+}
+
+#[derive(Debug)]
+pub struct IconThemes {
+    base_dirs: Vec<PathBuf>,
+    // `None` when the theme isn't installed
+    themes: HashMap<String, Option<IconTheme>>,
+}
+
+impl IconThemes {
+    pub fn new(base_dirs: Vec<PathBuf>) -> Self {
+        IconThemes {
+            base_dirs,
+            themes: HashMap::new(),
+        }
+    }
+
+    pub fn find(
+        &mut self,
+        name: &str,
+        themes: &[String],
+        size: u16,
+        scale: u16,
+    ) -> Option<PathBuf> {
+        // Symbolic icons are recolored, only svg can be
+        let extensions: &[&str] = if name.ends_with("-symbolic") {
+            &["svg"]
+        } else {
+            &["png", "svg", "xpm"]
+        };
+
+        let chain = self.chain(themes);
+        chain
+            .iter()
+            .filter_map(|theme| self.themes.get(theme).and_then(Option::as_ref))
+            .find_map(|theme| theme.find(name, size, scale, extensions))
+            .or_else(|| self.find_unthemed(name, extensions))
+    }
+
+    // The configured themes followed by their parents, depth first, and hicolor
+    pub fn chain(&mut self, themes: &[String]) -> Vec<String> {
+        let mut chain = vec![];
+        for theme in themes {
+            self.push_with_parents(theme, &mut chain);
+        }
+
+        self.push_with_parents(DEFAULT_THEME, &mut chain);
+        chain
+    }
+
+    fn push_with_parents(&mut self, name: &str, chain: &mut Vec<String>) {
+        // Also protects against inheritance cycles
+        if chain.iter().any(|theme| theme == name) {
+            return;
+        }
+
+        let base_dirs = &self.base_dirs;
+        let theme = self
+            .themes
+            .entry(name.to_string())
+            .or_insert_with(|| IconTheme::load(name, base_dirs));
+
+        let parents = match theme {
+            Some(theme) => theme.inherits.clone(),
+            None => return,
+        };
+
+        chain.push(name.to_string());
+        for parent in parents {
+            self.push_with_parents(&parent, chain);
+        }
+    }
+
+    fn find_unthemed(&self, name: &str, extensions: &[&str]) -> Option<PathBuf> {
+        self.base_dirs.iter().find_map(|dir| {
+            extensions
+                .iter()
+                .map(|extension| dir.join(format!("{name}.{extension}")))
+                .find(|path| path.is_file())
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct IconTheme {
+    // The theme directory in each base directory providing it
+    roots: Vec<PathBuf>,
+    inherits: Vec<String>,
+    directories: Vec<Directory>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Directory {
+    path: String,
+    size: u16,
+    scale: u16,
+    kind: DirectoryKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DirectoryKind {
+    Fixed,
+    Scalable { min: u16, max: u16 },
+    Threshold(u16),
+}
+
+impl IconTheme {
+    fn load(name: &str, base_dirs: &[PathBuf]) -> Option<Self> {
+        let roots: Vec<PathBuf> = base_dirs
+            .iter()
+            .map(|dir| dir.join(name))
+            .filter(|root| root.is_dir())
+            .collect();
+
+        let index = roots
+            .iter()
+            .find_map(|root| fs::read_to_string(root.join("index.theme")).ok())?;
+
+        let (inherits, directories) = parse_index(&index);
+        Some(IconTheme {
+            roots,
+            inherits,
+            directories,
+        })
+    }
+
+    fn find(&self, name: &str, size: u16, scale: u16, extensions: &[&str]) -> Option<PathBuf> {
+        let candidates = |directory: &Directory| -> Option<PathBuf> {
+            self.roots.iter().find_map(|root| {
+                extensions
+                    .iter()
+                    .map(|extension| {
+                        root.join(&directory.path)
+                            .join(format!("{name}.{extension}"))
+                    })
+                    .find(|path| path.is_file())
+            })
+        };
+
+        let exact = self
+            .directories
+            .iter()
+            .filter(|directory| directory.matches(size, scale))
+            .find_map(candidates);
+
+        exact.or_else(|| {
+            let mut directories: Vec<&Directory> = self.directories.iter().collect();
+            directories.sort_by_key(|directory| directory.distance(size, scale));
+            directories.into_iter().find_map(candidates)
+        })
+    }
+}
+
+impl Directory {
+    fn bounds(&self) -> (u16, u16) {
+        match self.kind {
+            DirectoryKind::Fixed => (self.size, self.size),
+            DirectoryKind::Scalable { min, max } => (min, max),
+            DirectoryKind::Threshold(threshold) => (
+                self.size.saturating_sub(threshold),
+                self.size.saturating_add(threshold),
+            ),
+        }
+    }
+
+    fn matches(&self, size: u16, scale: u16) -> bool {
+        let (min, max) = self.bounds();
+        self.scale == scale && min <= size && size <= max
+    }
+
+    // Distance in physical pixels between the directory and the requested size
+    fn distance(&self, size: u16, scale: u16) -> u32 {
+        let (min, max) = self.bounds();
+        let wanted = u32::from(size) * u32::from(scale);
+        let min = u32::from(min) * u32::from(self.scale);
+        let max = u32::from(max) * u32::from(self.scale);
+
+        if wanted < min {
+            min - wanted
+        } else {
+            wanted.saturating_sub(max)
+        }
+    }
+}
+
+// Returns the inherited themes and the icon directories declared in `index.theme`
+fn parse_index(content: &str) -> (Vec<String>, Vec<Directory>) {
+    let mut sections: HashMap<&str, HashMap<&str, &str>> = HashMap::new();
+    let mut section = "";
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') && line.ends_with(']') {
+            section = &line[1..line.len() - 1];
+        } else if let Some((key, value)) = line.split_once('=') {
+            sections
+                .entry(section)
+                .or_default()
+                .insert(key.trim(), value.trim());
+        }
+    }
+
+    let header = sections.get("Icon Theme").cloned().unwrap_or_default();
+    let list = |key: &str| -> Vec<String> {
+        header
+            .get(key)
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let inherits = list("Inherits");
+    let directories = list("Directories")
+        .into_iter()
+        .chain(list("ScaledDirectories"))
+        .filter_map(|path| {
+            let keys = sections.get(path.as_str())?;
+            let value = |key: &str| keys.get(key).and_then(|value| value.parse::<u16>().ok());
+            let size = value("Size")?;
+            let kind = match keys.get("Type").copied() {
+                Some("Fixed") => DirectoryKind::Fixed,
+                Some("Scalable") => DirectoryKind::Scalable {
+                    min: value("MinSize").unwrap_or(size),
+                    max: value("MaxSize").unwrap_or(size),
+                },
+                _ => DirectoryKind::Threshold(value("Threshold").unwrap_or(2)),
+            };
+
+            Some(Directory {
+                path,
+                size,
+                scale: value("Scale").unwrap_or(1),
+                kind,
+            })
+        })
+        .collect();
+
+    (inherits, directories)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::fixtures::fixture_dir;
+    use crate::icons::theme::IconThemes;
+    use speculoos::prelude::*;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn theme(base: &Path, name: &str, inherits: &str, directories: &[(&str, &str)]) {
+        let mut index = format!(
+            "[Icon Theme]\nName={name}\nInherits={inherits}\nDirectories={}\n",
+            directories
+                .iter()
+                .map(|(path, _)| *path)
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+
+        for (path, properties) in directories {
+            index.push_str(&format!("\n[{path}]\n{properties}\n"));
+            fs::create_dir_all(base.join(name).join(path)).unwrap();
+        }
+
+        fs::write(base.join(name).join("index.theme"), index).unwrap();
+    }
+
+    fn icon(base: &Path, path: &str) -> PathBuf {
+        let icon = base.join(path);
+        fs::create_dir_all(icon.parent().unwrap()).unwrap();
+        fs::write(&icon, "icon").unwrap();
+        icon
+    }
+
+    fn themes(dir: &str) -> (IconThemes, PathBuf) {
+        let base = fixture_dir(dir);
+        let pixmaps = base.join("pixmaps");
+
+        theme(
+            &base,
+            "Custom",
+            "Parent",
+            &[("16x16/apps", "Size=16\nType=Fixed")],
+        );
+        theme(
+            &base,
+            "Parent",
+            "Custom",
+            &[
+                ("24x24/apps", "Size=24\nType=Fixed"),
+                ("24x24@2/apps", "Size=24\nScale=2\nType=Fixed"),
+                ("48x48/apps", "Size=48\nType=Fixed"),
+            ],
+        );
+        theme(
+            &base,
+            "hicolor",
+            "",
+            &[(
+                "scalable/apps",
+                "Size=64\nMinSize=8\nMaxSize=512\nType=Scalable",
+            )],
+        );
+
+        icon(&base, "Custom/16x16/apps/terminal.png");
+        icon(&base, "Parent/24x24/apps/terminal.png");
+        icon(&base, "Parent/24x24/apps/firefox.png");
+        icon(&base, "Parent/24x24@2/apps/firefox.png");
+        icon(&base, "Parent/48x48/apps/editor.png");
+        icon(&base, "hicolor/scalable/apps/steam.svg");
+        icon(&pixmaps, "xterm.xpm");
+
+        (IconThemes::new(vec![base.clone(), pixmaps]), base)
+    }
+
+    #[test]
+    fn should_follow_theme_inheritance() {
+        let (mut themes, base) = themes("icon-themes-inheritance");
+        let configured = vec!["Missing".to_string(), "Custom".to_string()];
+
+        assert_that!(themes.chain(&configured)).is_equal_to(vec![
+            "Custom".to_string(),
+            "Parent".to_string(),
+            "hicolor".to_string(),
+        ]);
+        assert_that!(themes.find("terminal", &configured, 16, 1))
+            .is_equal_to(Some(base.join("Custom/16x16/apps/terminal.png")));
+        assert_that!(themes.find("terminal", &configured, 24, 1))
+            .is_equal_to(Some(base.join("Custom/16x16/apps/terminal.png")));
+        assert_that!(themes.find("steam", &configured, 24, 1))
+            .is_equal_to(Some(base.join("hicolor/scalable/apps/steam.svg")));
+        assert_that!(themes.find("xterm", &configured, 24, 1))
+            .is_equal_to(Some(base.join("pixmaps/xterm.xpm")));
+        assert_that!(themes.find("missing", &configured, 24, 1)).is_equal_to(None);
+    }
+
+    #[test]
+    fn should_pick_scaled_and_closest_directories() {
+        let (mut themes, base) = themes("icon-themes-scale");
+        let configured = vec!["Parent".to_string()];
+
+        assert_that!(themes.find("firefox", &configured, 24, 2))
+            .is_equal_to(Some(base.join("Parent/24x24@2/apps/firefox.png")));
+        assert_that!(themes.find("editor", &configured, 24, 2))
+            .is_equal_to(Some(base.join("Parent/48x48/apps/editor.png")));
+        assert_that!(themes.find("terminal", &configured, 24, 2))
+            .is_equal_to(Some(base.join("Parent/24x24/apps/terminal.png")));
+    }
+}
@@ -0,0 +1,139 @@
+use anyhow::{anyhow, Result};
+use image::{Rgba, RgbaImage};
+use std::collections::HashMap;
+
+const TRANSPARENT: Rgba<u8> = Rgba([0, 0, 0, 0]);
+
+// Icons larger than this are most likely not icons
+const MAX_SIZE: u32 = 1024;
+
+// Minimal XPM3 decoder, enough for the legacy icons still shipped in `/usr/share/pixmaps`
+pub fn decode(content: &str) -> Result<RgbaImage> {
+    let mut strings = quoted_strings(content).into_iter();
+    let header = strings
+        .next()
+        .ok_or_else(|| anyhow!("Missing XPM header"))?;
+    let values: Vec<u32> = header
+        .split_whitespace()
+        .map(str::parse)
+        .collect::<Result<_, _>>()?;
+
+    let (width, height, colors, chars_per_pixel) = match values.as_slice() {
+        [width, height, colors, chars_per_pixel, ..]
+            if *chars_per_pixel > 0 && *width <= MAX_SIZE && *height <= MAX_SIZE =>
+        {
+            (*width, *height, *colors, *chars_per_pixel as usize)
+        }
+        _ => return Err(anyhow!("Invalid XPM header '{header}'")),
+    };
+
+    let mut palette = HashMap::new();
+    for _ in 0..colors {
+        let line = strings.next().ok_or_else(|| anyhow!("Missing XPM color"))?;
+        let key = line
+            .get(..chars_per_pixel)
+            .ok_or_else(|| anyhow!("Invalid XPM color '{line}'"))?;
+        palette.insert(key, parse_color(&line[chars_per_pixel..]));
+    }
+
+    let mut image = RgbaImage::new(width, height);
+    for y in 0..height {
+        let row = strings
+            .next()
+            .ok_or_else(|| anyhow!("Missing XPM row {y}"))?;
+        for x in 0..width {
+            let start = x as usize * chars_per_pixel;
+            let pixel = row
+                .get(start..start + chars_per_pixel)
+                .and_then(|key| palette.get(key))
+                .copied()
+                .unwrap_or(TRANSPARENT);
+            image.put_pixel(x, y, pixel);
+        }
+    }
+
+    Ok(image)
+}
+
+// The image is a C array of strings, anything outside quotes is ignored
+fn quoted_strings(content: &str) -> Vec<&str> {
+    content.split('"').skip(1).step_by(2).collect()
+}
+
+// Color definitions look like `c #ff0000`, `s background c None` or `g grey c white`,
+// only the color visual (`c`) is used
+fn parse_color(definition: &str) -> Rgba<u8> {
+    let tokens: Vec<&str> = definition.split_whitespace().collect();
+    let color = tokens
+        .iter()
+        .position(|token| *token == "c")
+        .and_then(|idx| tokens.get(idx + 1))
+        .or_else(|| tokens.last());
+
+    match color {
+        Some(color) if color.eq_ignore_ascii_case("none") => TRANSPARENT,
+        Some(color) if color.starts_with('#') => parse_hex(&color[1..]).unwrap_or(TRANSPARENT),
+        Some(color) => named_color(color),
+        None => TRANSPARENT,
+    }
+}
+
+// `#rgb`, `#rrggbb` and `#rrrrggggbbbb`, keeping the most significant byte of each channel
+fn parse_hex(hex: &str) -> Option<Rgba<u8>> {
+    let digits = hex.len() / 3;
+    if digits == 0 || digits * 3 != hex.len() {
+        return None;
+    }
+
+    let channel = |idx: usize| -> Option<u8> {
+        let value = u32::from_str_radix(hex.get(idx * digits..(idx + 1) * digits)?, 16).ok()?;
+        Some(match digits {
+            1 => (value * 17) as u8,
+            2 => value as u8,
+            _ => (value >> ((digits - 2) * 4)) as u8,
+        })
+    };
+
+    Some(Rgba([channel(0)?, channel(1)?, channel(2)?, 255]))
+}
+
+fn named_color(name: &str) -> Rgba<u8> {
+    let [r, g, b] = match name.to_lowercase().as_str() {
+        "white" => [255, 255, 255],
+        "red" => [255, 0, 0],
+        "green" => [0, 255, 0],
+        "blue" => [0, 0, 255],
+        "yellow" => [255, 255, 0],
+        "gray" | "grey" => [190, 190, 190],
+        _ => [0, 0, 0],
+    };
+
+    Rgba([r, g, b, 255])
+}
+
+#[cfg(test)]
+mod test {
+    use crate::icons::xpm::decode;
+    use image::Rgba;
+    use speculoos::prelude::*;
+
+    #[test]
+    fn should_decode_xpm() {
+        let xpm = r#"/* XPM */
+static char *icon[] = {
+"3 2 3 1",
+"  c None",
+"r c #FF0000",
+"b s border c #00f",
+"rb ",
+" rb"
+};"#;
+
+        let image = decode(xpm).unwrap();
+
+        assert_that!(image.dimensions()).is_equal_to((3, 2));
+        assert_that!(*image.get_pixel(0, 0)).is_equal_to(Rgba([255, 0, 0, 255]));
+        assert_that!(*image.get_pixel(1, 0)).is_equal_to(Rgba([0, 0, 255, 255]));
+        assert_that!(*image.get_pixel(2, 0)).is_equal_to(Rgba([0, 0, 0, 0]));
+    }
+}
@@ -0,0 +1,34 @@
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use app::style::Theme;
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+
+pub mod app;
+pub mod bookmarks;
+pub mod calc;
+pub mod config;
+pub mod db;
+pub mod emoji;
+pub mod engines;
+pub mod font;
+pub mod freedesktop;
+pub mod icons;
+pub mod scripts;
+pub mod ssh;
+pub mod windows;
+
+#[cfg(test)]
+mod fixtures;
+
+pub static THEME_PATH: Lazy<Mutex<PathBuf>> = Lazy::new(|| {
+    Mutex::new(
+        dirs::config_dir()
+            .ok_or_else(|| anyhow!("Theme config not found"))
+            .map(|path| path.join("onagre").join("theme.toml"))
+            .unwrap(),
+    )
+});
+
+pub static THEME: Lazy<Theme> = Lazy::new(Theme::load);
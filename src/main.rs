@@ -1,30 +1,9 @@
 use std::path::PathBuf;
 
-use anyhow::anyhow;
-use app::style::Theme;
 use log::debug;
-use once_cell::sync::Lazy;
-use std::sync::Mutex;
+use onagre::{app, THEME_PATH};
 use structopt::StructOpt;
 
-pub mod app;
-pub mod config;
-pub mod db;
-pub mod font;
-pub mod freedesktop;
-pub mod icons;
-
-pub static THEME_PATH: Lazy<Mutex<PathBuf>> = Lazy::new(|| {
-    Mutex::new(
-        dirs::config_dir()
-            .ok_or_else(|| anyhow!("Theme config not found"))
-            .map(|path| path.join("onagre").join("theme.toml"))
-            .unwrap(),
-    )
-});
-
-pub static THEME: Lazy<Theme> = Lazy::new(Theme::load);
-
 #[derive(StructOpt)]
 #[structopt(name = "onagre", author = "Paul D. <paul.delafosse@protonmail.com>")]
 struct Cli {
@@ -0,0 +1,132 @@
+use log::error;
+use std::path::PathBuf;
+use std::process::Stdio;
+use tokio::process::Command;
+
+// Value of `ONAGRE_RETV` passed to the script, like rofi's `ROFI_RETV`
+const RETV_SEARCH: &str = "0";
+const RETV_SELECTED: &str = "1";
+
+// A custom mode declared in the theme with a `.script-mode` block.
+// The script is run with the query as its last argument and prints one entry per line,
+// optionally followed by rofi style metadata: `text\0icon\x1ffirefox\x1finfo\x1fdata`.
+// When an entry is activated the script is run again with the entry text,
+// if it prints nothing onagre exits, otherwise the output replaces the current entries.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ScriptMode {
+    pub name: String,
+    pub prefix: String,
+    pub icon: Option<String>,
+    pub command: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ScriptEntry {
+    pub text: String,
+    pub icon: Option<String>,
+    // Passed back to the script in `ONAGRE_INFO` when the entry is activated
+    pub info: Option<String>,
+}
+
+impl ScriptMode {
+    pub async fn search(&self, query: String) -> Vec<ScriptEntry> {
+        self.run(&query, RETV_SEARCH, None).await
+    }
+
+    pub async fn activate(&self, entry: ScriptEntry) -> Vec<ScriptEntry> {
+        self.run(&entry.text, RETV_SELECTED, entry.info.as_deref())
+            .await
+    }
+
+    async fn run(&self, argument: &str, retv: &str, info: Option<&str>) -> Vec<ScriptEntry> {
+        let mut args = match shell_words::split(&self.command) {
+            Ok(args) if !args.is_empty() => args,
+            _ => {
+                error!("Invalid command for script mode '{}'", self.name);
+                return vec![];
+            }
+        };
+
+        let program = expand_home(&args.remove(0));
+        let mut command = Command::new(program);
+        command
+            .args(args)
+            .arg(argument)
+            .env("ONAGRE_RETV", retv)
+            .stdin(Stdio::null())
+            .stderr(Stdio::inherit());
+
+        if let Some(info) = info {
+            command.env("ONAGRE_INFO", info);
+        }
+
+        match command.output().await {
+            Ok(output) => parse_output(&String::from_utf8_lossy(&output.stdout)),
+            Err(err) => {
+                error!("Failed to run script mode '{}': {err}", self.name);
+                vec![]
+            }
+        }
+    }
+}
+
+pub fn parse_output(output: &str) -> Vec<ScriptEntry> {
+    output
+        .lines()
+        // Lines starting with `\0` are rofi mode options, we don't support them
+        .filter(|line| !line.is_empty() && !line.starts_with('\0'))
+        .map(parse_line)
+        .collect()
+}
+
+fn parse_line(line: &str) -> ScriptEntry {
+    let (text, options) = line.split_once('\0').unwrap_or((line, ""));
+    let mut entry = ScriptEntry {
+        text: text.to_string(),
+        ..Default::default()
+    };
+
+    let mut options = options.split('\x1f');
+    while let (Some(key), Some(value)) = (options.next(), options.next()) {
+        match key {
+            "icon" => entry.icon = Some(value.to_string()),
+            "info" => entry.info = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    entry
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(path), Some(home)) => home.join(path),
+        _ => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::scripts::{parse_output, ScriptEntry};
+    use speculoos::prelude::*;
+
+    #[test]
+    fn should_parse_script_output() {
+        let output = "\0prompt\x1fNotes\nfirst\nsecond\0icon\x1ftext-x-generic\x1finfo\x1f2\n\n";
+
+        let entries = parse_output(output);
+
+        assert_that!(entries).is_equal_to(vec![
+            ScriptEntry {
+                text: "first".to_string(),
+                icon: None,
+                info: None,
+            },
+            ScriptEntry {
+                text: "second".to_string(),
+                icon: Some("text-x-generic".to_string()),
+                info: Some("2".to_string()),
+            },
+        ]);
+    }
+}
@@ -0,0 +1,350 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Connection history collection, used to rank hosts
+pub const COLLECTION: &str = "ssh";
+
+// Nested `Include` directives deeper than this are ignored, like ssh does
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SshHost {
+    // The `Host` alias or the known_hosts entry, passed to `ssh`
+    pub name: String,
+    pub hostname: Option<String>,
+    pub user: Option<String>,
+    pub port: Option<u16>,
+}
+
+impl SshHost {
+    fn new(name: &str) -> Self {
+        SshHost {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        self.name.to_lowercase().contains(&query)
+            || self
+                .hostname
+                .iter()
+                .chain(self.user.iter())
+                .any(|value| value.to_lowercase().contains(&query))
+    }
+
+    pub fn command(&self) -> Vec<String> {
+        let mut command = vec!["ssh".to_string()];
+        if let Some(port) = self.port {
+            command.push("-p".to_string());
+            command.push(port.to_string());
+        }
+
+        command.push(self.name.clone());
+        command
+    }
+
+    // `user@hostname:port`, omitting what is unknown
+    pub fn target(&self) -> String {
+        let mut target = String::new();
+        if let Some(user) = &self.user {
+            target.push_str(user);
+            target.push('@');
+        }
+
+        target.push_str(self.hostname.as_deref().unwrap_or(&self.name));
+        if let Some(port) = self.port {
+            target.push_str(&format!(":{port}"));
+        }
+
+        target
+    }
+}
+
+// Hosts from `~/.ssh/config` followed by the ones only found in `~/.ssh/known_hosts`
+pub fn hosts() -> Vec<SshHost> {
+    match dirs::home_dir() {
+        Some(home) => load_hosts(&home.join(".ssh")),
+        None => vec![],
+    }
+}
+
+pub fn load_hosts(ssh_dir: &Path) -> Vec<SshHost> {
+    let mut hosts = vec![];
+    parse_config(&ssh_dir.join("config"), ssh_dir, 0, &mut hosts);
+
+    let known_hosts = fs::read_to_string(ssh_dir.join("known_hosts")).unwrap_or_default();
+    for host in parse_known_hosts(&known_hosts) {
+        if !hosts.iter().any(|known: &SshHost| known.name == host.name) {
+            hosts.push(host);
+        }
+    }
+
+    hosts
+}
+
+fn parse_config(path: &Path, ssh_dir: &Path, depth: usize, hosts: &mut Vec<SshHost>) {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return,
+    };
+
+    // Indices of the hosts declared by the current `Host` block
+    let mut current: Vec<usize> = vec![];
+
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        // Keyword and arguments are separated by whitespace or `=`
+        let separator = |c: char| c.is_whitespace() || c == '=';
+        let (keyword, args) = match line.split_once(separator) {
+            Some((keyword, args)) => (keyword, args.trim_start_matches(separator)),
+            None => continue,
+        };
+
+        let args = args.trim();
+        match keyword.to_lowercase().as_str() {
+            "host" => {
+                current.clear();
+                // Patterns can't be connected to
+                for name in args
+                    .split_whitespace()
+                    .filter(|name| !name.contains(['*', '?', '!']))
+                {
+                    match hosts.iter().position(|host| host.name == name) {
+                        Some(idx) => current.push(idx),
+                        None => {
+                            hosts.push(SshHost::new(name));
+                            current.push(hosts.len() - 1);
+                        }
+                    }
+                }
+            }
+            "match" => current.clear(),
+            "include" if depth < MAX_INCLUDE_DEPTH => {
+                for pattern in args.split_whitespace() {
+                    for include in expand_include(pattern, ssh_dir) {
+                        parse_config(&include, ssh_dir, depth + 1, hosts);
+                    }
+                }
+            }
+            // Like ssh, the first obtained value wins
+            "hostname" => current.iter().for_each(|idx| {
+                hosts[*idx].hostname.get_or_insert_with(|| args.to_string());
+            }),
+            "user" => current.iter().for_each(|idx| {
+                hosts[*idx].user.get_or_insert_with(|| args.to_string());
+            }),
+            "port" => {
+                if let Ok(port) = args.parse::<u16>() {
+                    current.iter().for_each(|idx| {
+                        hosts[*idx].port.get_or_insert(port);
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Relative includes are resolved from `~/.ssh`, wildcards are supported in the file name
+fn expand_include(pattern: &str, ssh_dir: &Path) -> Vec<PathBuf> {
+    let path = match pattern.strip_prefix("~/") {
+        Some(relative) => match dirs::home_dir() {
+            Some(home) => home.join(relative),
+            None => return vec![],
+        },
+        None => ssh_dir.join(pattern),
+    };
+
+    let file_name = match path.file_name().and_then(|name| name.to_str()) {
+        Some(file_name) => file_name.chars().collect::<Vec<char>>(),
+        None => return vec![],
+    };
+
+    if !file_name.iter().any(|c| *c == '*' || *c == '?') {
+        return vec![path];
+    }
+
+    let dir = path.parent().unwrap_or(ssh_dir);
+    let mut includes: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(
+            |include| match include.file_name().and_then(|name| name.to_str()) {
+                Some(name) => wildcard_match(&file_name, &name.chars().collect::<Vec<char>>()),
+                None => false,
+            },
+        )
+        .collect();
+
+    // ssh includes files in lexical order
+    includes.sort();
+    includes
+}
+
+fn wildcard_match(pattern: &[char], text: &[char]) -> bool {
+    match (pattern.first(), text.first()) {
+        (None, None) => true,
+        (Some('*'), _) => {
+            wildcard_match(&pattern[1..], text)
+                || (!text.is_empty() && wildcard_match(pattern, &text[1..]))
+        }
+        (Some('?'), Some(_)) => wildcard_match(&pattern[1..], &text[1..]),
+        (Some(expected), Some(actual)) if expected == actual => {
+            wildcard_match(&pattern[1..], &text[1..])
+        }
+        _ => false,
+    }
+}
+
+// Each line reads `host1,[host2]:port key-type key`,
+// hashed hosts and markers such as `@revoked` are skipped
+fn parse_known_hosts(content: &str) -> Vec<SshHost> {
+    let mut hosts: Vec<SshHost> = vec![];
+
+    let names = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with(['#', '@', '|']))
+        .filter_map(|line| line.split_whitespace().next())
+        .flat_map(|names| names.split(','))
+        .filter(|name| !name.contains(['*', '?', '!']));
+
+    for name in names {
+        let host = match name
+            .strip_prefix('[')
+            .and_then(|name| name.split_once("]:"))
+        {
+            Some((name, port)) => SshHost {
+                port: port.parse().ok(),
+                ..SshHost::new(name)
+            },
+            None => SshHost::new(name),
+        };
+
+        if !hosts.contains(&host) {
+            hosts.push(host);
+        }
+    }
+
+    hosts
+}
+
+#[cfg(test)]
+mod test {
+    use crate::fixtures::fixture_dir;
+    use crate::ssh::{load_hosts, parse_known_hosts, SshHost};
+    use speculoos::prelude::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn ssh_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = fixture_dir(&format!("ssh-{name}"));
+        fs::create_dir_all(dir.join("config.d")).unwrap();
+
+        for (path, content) in files {
+            fs::write(dir.join(path), content).unwrap();
+        }
+
+        dir
+    }
+
+    #[test]
+    fn should_parse_host_blocks_and_includes() {
+        let dir = ssh_dir(
+            "config",
+            &[
+                (
+                    "config",
+                    "Include config.d/*.conf\n\nHost web db\n    User admin\n\nHost *.internal !bastion\n    User nobody\n",
+                ),
+                (
+                    "config.d/work.conf",
+                    "Host bastion\n  HostName bastion.example.com\n  User ops\n  Port=2222\n",
+                ),
+                ("config.d/ignored", "Host ignored\n"),
+            ],
+        );
+
+        let hosts = load_hosts(&dir);
+
+        assert_that!(hosts).is_equal_to(vec![
+            SshHost {
+                name: "bastion".to_string(),
+                hostname: Some("bastion.example.com".to_string()),
+                user: Some("ops".to_string()),
+                port: Some(2222),
+            },
+            SshHost {
+                name: "web".to_string(),
+                user: Some("admin".to_string()),
+                ..Default::default()
+            },
+            SshHost {
+                name: "db".to_string(),
+                user: Some("admin".to_string()),
+                ..Default::default()
+            },
+        ]);
+    }
+
+    #[test]
+    fn should_split_keywords_on_unicode_whitespace() {
+        let dir = ssh_dir(
+            "unicode",
+            &[(
+                "config",
+                "Host\u{a0}nas\n  User\u{3000}admin\n  Port =\u{3000}2222\n",
+            )],
+        );
+
+        let hosts = load_hosts(&dir);
+
+        assert_that!(hosts).is_equal_to(vec![SshHost {
+            name: "nas".to_string(),
+            user: Some("admin".to_string()),
+            port: Some(2222),
+            ..Default::default()
+        }]);
+    }
+
+    #[test]
+    fn should_parse_known_hosts() {
+        let content = "github.com,140.82.121.3 ssh-ed25519 AAAA\n\
+            [git.example.com]:2222 ssh-rsa AAAA\n\
+            |1|hashed= ssh-rsa AAAA\n\
+            @revoked revoked.com ssh-rsa AAAA\n";
+
+        let hosts = parse_known_hosts(content);
+
+        assert_that!(hosts).is_equal_to(vec![
+            SshHost::new("github.com"),
+            SshHost::new("140.82.121.3"),
+            SshHost {
+                port: Some(2222),
+                ..SshHost::new("git.example.com")
+            },
+        ]);
+    }
+
+    #[test]
+    fn should_build_ssh_command() {
+        let host = SshHost {
+            port: Some(2222),
+            ..SshHost::new("git.example.com")
+        };
+
+        assert_that!(host.command()).is_equal_to(vec![
+            "ssh".to_string(),
+            "-p".to_string(),
+            "2222".to_string(),
+            "git.example.com".to_string(),
+        ]);
+    }
+}
@@ -0,0 +1,192 @@
+use crate::windows::{Output, Window};
+use anyhow::{anyhow, Result};
+use std::process::Command;
+
+// List windows with `wmctrl -lx`, each line reads:
+// <window id> <desktop> <instance.class> <host> <title>
+pub fn windows() -> Result<Vec<Window>> {
+    let output = Command::new("wmctrl").arg("-lx").output()?;
+
+    if !output.status.success() {
+        return Err(anyhow!("wmctrl exited with {}", output.status));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_line)
+        .collect())
+}
+
+pub fn focus(id: &str) -> Result<()> {
+    let status = Command::new("wmctrl").args(["-i", "-a", id]).status()?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!("wmctrl exited with {status}"))
+    }
+}
+
+// Applied by the compositor, e.g. picom
+pub fn set_opacity(title: &str, opacity: f32) -> Result<()> {
+    let opacity = (f64::from(opacity) * f64::from(u32::MAX)).round() as u32;
+    let status = Command::new("xprop")
+        .args(["-name", title, "-f", "_NET_WM_WINDOW_OPACITY", "32c"])
+        .args(["-set", "_NET_WM_WINDOW_OPACITY", &opacity.to_string()])
+        .status()?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!("xprop exited with {status}"))
+    }
+}
+
+// X11 has no notion of focused monitor, the one under the pointer is used
+pub fn outputs(cursor: Option<(i32, i32)>) -> Result<Vec<Output>> {
+    let output = Command::new("xrandr").arg("--query").output()?;
+
+    if !output.status.success() {
+        return Err(anyhow!("xrandr exited with {}", output.status));
+    }
+
+    let mut outputs: Vec<Output> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_output)
+        .collect();
+
+    for output in &mut outputs {
+        output.focused = match cursor {
+            Some(cursor) => output.contains(cursor),
+            None => output.primary,
+        };
+    }
+
+    Ok(outputs)
+}
+
+pub fn cursor() -> Option<(i32, i32)> {
+    let output = Command::new("xdotool")
+        .args(["getmouselocation", "--shell"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+
+    let output = String::from_utf8_lossy(&output.stdout);
+    let value = |key: &str| {
+        output
+            .lines()
+            .find_map(|line| line.strip_prefix(key))
+            .and_then(|value| value.trim().parse::<i32>().ok())
+    };
+
+    Some((value("X=")?, value("Y=")?))
+}
+
+// Connected outputs read:
+// <name> connected [primary] <width>x<height>+<x>+<y> ...
+fn parse_output(line: &str) -> Option<Output> {
+    let mut columns = line.split_whitespace();
+    let name = columns.next()?;
+
+    if columns.next()? != "connected" {
+        return None;
+    }
+
+    let mut geometry = columns.next()?;
+    let primary = geometry == "primary";
+    if primary {
+        geometry = columns.next()?;
+    }
+
+    let (size, position) = geometry.split_once('+')?;
+    let (width, height) = size.split_once('x')?;
+    let (x, y) = position.split_once('+')?;
+
+    Some(Output {
+        name: name.to_string(),
+        x: x.parse().ok()?,
+        y: y.parse().ok()?,
+        width: width.parse().ok()?,
+        height: height.parse().ok()?,
+        focused: false,
+        primary,
+        scale: None,
+    })
+}
+
+fn parse_line(line: &str) -> Option<Window> {
+    let mut columns = Vec::with_capacity(4);
+    let mut rest = line;
+
+    for _ in 0..4 {
+        let trimmed = rest.trim_start();
+        let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+        if end == 0 {
+            return None;
+        }
+
+        columns.push(&trimmed[..end]);
+        rest = &trimmed[end..];
+    }
+
+    // The WM_CLASS instance is lowercase and usually matches the icon name
+    let app_id = columns[2].split('.').next().map(str::to_string);
+
+    // Sticky windows are displayed on every desktop
+    let workspace = match columns[1] {
+        "-1" => None,
+        desktop => desktop.parse::<u32>().ok().map(|idx| (idx + 1).to_string()),
+    };
+
+    Some(Window {
+        id: columns[0].to_string(),
+        title: rest.trim().to_string(),
+        app_id,
+        workspace,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::windows::ewmh::{parse_line, parse_output};
+    use crate::windows::{Output, Window};
+    use speculoos::prelude::*;
+
+    #[test]
+    fn should_parse_wmctrl_line() {
+        let line = "0x02a00003  1 firefox.Firefox       hostname Mozilla  Firefox";
+
+        let window = parse_line(line);
+
+        assert_that!(window).is_equal_to(Some(Window {
+            id: "0x02a00003".to_string(),
+            title: "Mozilla  Firefox".to_string(),
+            app_id: Some("firefox".to_string()),
+            workspace: Some("2".to_string()),
+        }));
+    }
+
+    #[test]
+    fn should_parse_xrandr_outputs() {
+        let primary = "eDP-1 connected primary 1920x1080+0+0 (normal left inverted) 344mm x 193mm";
+        let secondary = "HDMI-1 connected 2560x1440+1920+0 (normal left inverted) 597mm x 336mm";
+
+        assert_that!(parse_output(primary)).is_equal_to(Some(Output {
+            name: "eDP-1".to_string(),
+            x: 0,
+            y: 0,
+            width: 1920,
+            height: 1080,
+            focused: false,
+            primary: true,
+            scale: None,
+        }));
+        assert_that!(parse_output(secondary).map(|output| (output.x, output.width)))
+            .is_equal_to(Some((1920, 2560)));
+        assert_that!(parse_output("DP-1 disconnected (normal left inverted)")).is_none();
+        // Connected but disabled
+        assert_that!(parse_output("DP-2 connected (normal left inverted)")).is_none();
+        assert_that!(parse_output("   1920x1080     60.00*+")).is_none();
+    }
+}
@@ -0,0 +1,351 @@
+use crate::windows::{Output, Window};
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+
+// See https://i3wm.org/docs/ipc.html, sway implements the same protocol
+const MAGIC: &[u8; 6] = b"i3-ipc";
+const HEADER_LEN: usize = 14;
+const RUN_COMMAND: u32 = 0;
+const GET_WORKSPACES: u32 = 1;
+const GET_OUTPUTS: u32 = 3;
+const GET_TREE: u32 = 4;
+const SCRATCHPAD: &str = "__i3_scratch";
+
+#[derive(Debug, Deserialize)]
+struct Node {
+    id: i64,
+    name: Option<String>,
+    #[serde(rename = "type")]
+    node_type: String,
+    // Native wayland windows on sway
+    app_id: Option<String>,
+    // X11 window id, on i3 and for xwayland windows on sway
+    window: Option<u64>,
+    window_properties: Option<WindowProperties>,
+    #[serde(default)]
+    nodes: Vec<Node>,
+    #[serde(default)]
+    floating_nodes: Vec<Node>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WindowProperties {
+    class: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Workspace {
+    output: String,
+    focused: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct IpcOutput {
+    name: String,
+    active: bool,
+    #[serde(default)]
+    primary: bool,
+    scale: Option<f32>,
+    rect: Rect,
+}
+
+#[derive(Debug, Deserialize)]
+struct Rect {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommandOutcome {
+    success: bool,
+    error: Option<String>,
+}
+
+pub fn windows(socket: &Path) -> Result<Vec<Window>> {
+    let tree: Node = serde_json::from_slice(&request(socket, GET_TREE, "")?)?;
+    let mut windows = vec![];
+    collect(&tree, None, &mut windows);
+    Ok(windows)
+}
+
+pub fn focus(socket: &Path, id: &str) -> Result<()> {
+    run_command(socket, &format!("[con_id={id}] focus"))
+}
+
+// The focused output is the one holding the focused workspace
+pub fn outputs(socket: &Path) -> Result<Vec<Output>> {
+    let outputs: Vec<IpcOutput> = serde_json::from_slice(&request(socket, GET_OUTPUTS, "")?)?;
+    let workspaces: Vec<Workspace> = serde_json::from_slice(&request(socket, GET_WORKSPACES, "")?)?;
+    let focused = workspaces
+        .iter()
+        .find(|workspace| workspace.focused)
+        .map(|workspace| workspace.output.as_str());
+
+    Ok(outputs
+        .into_iter()
+        .filter(|output| output.active)
+        .map(|output| Output {
+            focused: Some(output.name.as_str()) == focused,
+            name: output.name,
+            x: output.rect.x,
+            y: output.rect.y,
+            width: output.rect.width,
+            height: output.rect.height,
+            primary: output.primary,
+            scale: output.scale.filter(|scale| *scale > 0.0),
+        })
+        .collect())
+}
+
+// Floating sticky windows are never tiled and stay visible when switching workspaces
+pub fn float(socket: &Path, title: &str, (x, y): (i32, i32)) -> Result<()> {
+    run_command(
+        socket,
+        &format!(
+            "{} floating enable, sticky enable, move absolute position {x} {y}, focus",
+            title_criteria(title)
+        ),
+    )
+}
+
+pub fn set_opacity(socket: &Path, title: &str, opacity: f32) -> Result<()> {
+    run_command(
+        socket,
+        &format!("{} opacity {opacity:.2}", title_criteria(title)),
+    )
+}
+
+// Criteria values are PCRE patterns inside double quotes, match the title literally
+fn title_criteria(title: &str) -> String {
+    let mut pattern = String::with_capacity(title.len());
+    for c in title.chars() {
+        match c {
+            '"' => pattern.push_str("\\\""),
+            '\\' | '.' | '+' | '*' | '?' | '(' | ')' | '|' | '[' | ']' | '{' | '}' | '^' | '$' => {
+                pattern.push('\\');
+                pattern.push(c);
+            }
+            c => pattern.push(c),
+        }
+    }
+
+    format!("[title=\"^{pattern}$\"]")
+}
+
+fn run_command(socket: &Path, command: &str) -> Result<()> {
+    let reply = request(socket, RUN_COMMAND, command)?;
+    let outcomes: Vec<CommandOutcome> = serde_json::from_slice(&reply)?;
+
+    match outcomes.into_iter().find(|outcome| !outcome.success) {
+        Some(outcome) => Err(anyhow!(outcome
+            .error
+            .unwrap_or_else(|| format!("Failed to run '{command}'")))),
+        None => Ok(()),
+    }
+}
+
+// Windows are the tree leaves holding a client, either wayland or X11
+fn collect<'a>(node: &'a Node, workspace: Option<&'a str>, windows: &mut Vec<Window>) {
+    // i3 bars live in dock areas and hidden windows in the scratchpad workspace
+    if node.node_type == "dockarea" || node.name.as_deref() == Some(SCRATCHPAD) {
+        return;
+    }
+
+    let workspace = if node.node_type == "workspace" {
+        node.name.as_deref()
+    } else {
+        workspace
+    };
+
+    if node.app_id.is_some() || node.window.is_some() {
+        let app_id = node.app_id.clone().or_else(|| {
+            node.window_properties
+                .as_ref()
+                .and_then(|properties| properties.class.clone())
+        });
+
+        windows.push(Window {
+            id: node.id.to_string(),
+            title: node.name.clone().unwrap_or_default(),
+            app_id,
+            workspace: workspace.map(str::to_string),
+        });
+    }
+
+    for child in node.nodes.iter().chain(&node.floating_nodes) {
+        collect(child, workspace, windows);
+    }
+}
+
+fn encode(message_type: u32, payload: &[u8]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(HEADER_LEN + payload.len());
+    message.extend_from_slice(MAGIC);
+    message.extend_from_slice(&(payload.len() as u32).to_ne_bytes());
+    message.extend_from_slice(&message_type.to_ne_bytes());
+    message.extend_from_slice(payload);
+    message
+}
+
+// Read a message, returns its type and payload
+fn decode<R: Read>(reader: &mut R) -> Result<(u32, Vec<u8>)> {
+    let mut header = [0; HEADER_LEN];
+    reader.read_exact(&mut header)?;
+
+    if &header[..6] != MAGIC {
+        return Err(anyhow!("Invalid IPC message header"));
+    }
+
+    let len = u32::from_ne_bytes([header[6], header[7], header[8], header[9]]);
+    let message_type = u32::from_ne_bytes([header[10], header[11], header[12], header[13]]);
+    let mut payload = vec![0; len as usize];
+    reader.read_exact(&mut payload)?;
+
+    Ok((message_type, payload))
+}
+
+fn request(socket: &Path, message_type: u32, payload: &str) -> Result<Vec<u8>> {
+    let mut stream = UnixStream::connect(socket)?;
+    stream.write_all(&encode(message_type, payload.as_bytes()))?;
+    let (_, reply) = decode(&mut stream)?;
+    Ok(reply)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::windows::ipc::{decode, encode, float, title_criteria, GET_TREE, RUN_COMMAND};
+    use crate::windows::{Window, WindowManager};
+    use speculoos::prelude::*;
+    use std::os::unix::net::UnixListener;
+    use std::path::PathBuf;
+    use std::thread::{self, JoinHandle};
+
+    const TREE: &str = r#"{
+      "id": 1, "name": "root", "type": "root",
+      "nodes": [{
+        "id": 2, "name": "eDP-1", "type": "output",
+        "nodes": [{
+          "id": 3, "name": "2", "type": "workspace",
+          "nodes": [
+            { "id": 4, "name": "Mozilla Firefox", "type": "con", "app_id": "firefox", "window": null, "nodes": [] },
+            { "id": 5, "name": "Steam", "type": "con", "app_id": null, "window": 8388614,
+              "window_properties": { "class": "Steam" }, "nodes": [] }
+          ],
+          "floating_nodes": [
+            { "id": 6, "name": "onagre", "type": "floating_con", "app_id": "onagre", "nodes": [] }
+          ]
+        }, {
+          "id": 7, "name": "bottomdock", "type": "dockarea",
+          "nodes": [{ "id": 8, "name": "i3bar for output eDP-1", "type": "con", "window": 8388610, "nodes": [] }]
+        }]
+      }, {
+        "id": 9, "name": "__i3", "type": "output",
+        "nodes": [{
+          "id": 10, "name": "__i3_scratch", "type": "workspace",
+          "floating_nodes": [
+            { "id": 11, "name": "Notes", "type": "floating_con", "app_id": "notes", "nodes": [] }
+          ]
+        }]
+      }]
+    }"#;
+
+    // Answer a single request with `reply`, returns the request received
+    fn fake_ipc(name: &str, reply: &'static str) -> (PathBuf, JoinHandle<(u32, String)>) {
+        let socket =
+            std::env::temp_dir().join(format!("onagre-{}-{name}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&socket);
+        let listener = UnixListener::bind(&socket).unwrap();
+
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let (message_type, payload) = decode(&mut stream).unwrap();
+            std::io::Write::write_all(&mut stream, &encode(message_type, reply.as_bytes()))
+                .unwrap();
+            (message_type, String::from_utf8(payload).unwrap())
+        });
+
+        (socket, handle)
+    }
+
+    #[test]
+    fn should_list_windows_from_the_tree() {
+        let (socket, server) = fake_ipc("tree", TREE);
+
+        let windows = WindowManager::Ipc(socket).windows().unwrap();
+
+        assert_that!(server.join().unwrap()).is_equal_to((GET_TREE, String::new()));
+        assert_that!(windows).is_equal_to(vec![
+            Window {
+                id: "4".to_string(),
+                title: "Mozilla Firefox".to_string(),
+                app_id: Some("firefox".to_string()),
+                workspace: Some("2".to_string()),
+            },
+            Window {
+                id: "5".to_string(),
+                title: "Steam".to_string(),
+                app_id: Some("Steam".to_string()),
+                workspace: Some("2".to_string()),
+            },
+        ]);
+    }
+
+    #[test]
+    fn should_focus_window_by_container_id() {
+        let (socket, server) = fake_ipc("focus", r#"[{ "success": true }]"#);
+        let window = Window {
+            id: "4".to_string(),
+            title: "Mozilla Firefox".to_string(),
+            app_id: Some("firefox".to_string()),
+            workspace: None,
+        };
+
+        let focused = WindowManager::Ipc(socket).focus(&window);
+
+        assert_that!(focused).is_ok();
+        assert_that!(server.join().unwrap())
+            .is_equal_to((RUN_COMMAND, "[con_id=4] focus".to_string()));
+    }
+
+    #[test]
+    fn should_float_window_by_title() {
+        let (socket, server) = fake_ipc("float", r#"[{ "success": true }]"#);
+
+        let floated = float(&socket, "Onagre", (660, 108));
+
+        assert_that!(floated).is_ok();
+        assert_that!(server.join().unwrap()).is_equal_to((
+            RUN_COMMAND,
+            r#"[title="^Onagre$"] floating enable, sticky enable, move absolute position 660 108, focus"#
+                .to_string(),
+        ));
+    }
+
+    #[test]
+    fn should_escape_the_title_criteria() {
+        assert_that!(title_criteria(r#"Onagre (1.0) "beta" [*]"#))
+            .is_equal_to(r#"[title="^Onagre \(1\.0\) \"beta\" \[\*\]$"]"#.to_string());
+    }
+
+    #[test]
+    fn should_report_command_failure() {
+        let (socket, _server) = fake_ipc(
+            "failure",
+            r#"[{ "success": false, "error": "No matching node" }]"#,
+        );
+        let window = Window {
+            id: "42".to_string(),
+            title: String::new(),
+            app_id: None,
+            workspace: None,
+        };
+
+        let focused = WindowManager::Ipc(socket).focus(&window);
+
+        assert_that!(focused).is_err();
+    }
+}
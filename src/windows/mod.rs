@@ -0,0 +1,167 @@
+use anyhow::Result;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+mod ewmh;
+mod ipc;
+
+// An open window as reported by the window manager
+#[derive(Debug, Clone, PartialEq)]
+pub struct Window {
+    // sway/i3 container id or X11 window id
+    pub id: String,
+    pub title: String,
+    // Wayland app_id or X11 WM_CLASS, used to look up the window icon
+    pub app_id: Option<String>,
+    pub workspace: Option<String>,
+}
+
+impl Window {
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        self.title.to_lowercase().contains(&query)
+            || self
+                .app_id
+                .iter()
+                .any(|app_id| app_id.to_lowercase().contains(&query))
+    }
+}
+
+// A monitor, in the global coordinates of the window manager
+#[derive(Debug, Clone, PartialEq)]
+pub struct Output {
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub focused: bool,
+    pub primary: bool,
+    // Only reported by sway, i3 and xrandr don't know about output scaling
+    pub scale: Option<f32>,
+}
+
+impl Output {
+    pub fn contains(&self, (x, y): (i32, i32)) -> bool {
+        x >= self.x
+            && y >= self.y
+            && i64::from(x) < i64::from(self.x) + i64::from(self.width)
+            && i64::from(y) < i64::from(self.y) + i64::from(self.height)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WindowManager {
+    // sway and i3 share the same IPC protocol
+    Ipc(PathBuf),
+    // Any EWMH compliant X11 window manager, driven with `wmctrl`
+    Ewmh,
+}
+
+impl WindowManager {
+    pub fn detect() -> Option<Self> {
+        Self::detect_with(|key| std::env::var_os(key))
+    }
+
+    // Wayland compositors other than sway can't be driven, `DISPLAY` only points to
+    // xwayland there and wmctrl would not see native windows. wlr-foreign-toplevel
+    // would need a wayland client of its own, it is not supported.
+    fn detect_with<F>(var: F) -> Option<Self>
+    where
+        F: Fn(&str) -> Option<OsString>,
+    {
+        if let Some(socket) = var("SWAYSOCK").or_else(|| var("I3SOCK")) {
+            Some(WindowManager::Ipc(PathBuf::from(socket)))
+        } else if var("WAYLAND_DISPLAY").is_some() {
+            None
+        } else {
+            var("DISPLAY").map(|_| WindowManager::Ewmh)
+        }
+    }
+
+    pub fn windows(&self) -> Result<Vec<Window>> {
+        let windows = match self {
+            WindowManager::Ipc(socket) => ipc::windows(socket)?,
+            WindowManager::Ewmh => ewmh::windows()?,
+        };
+
+        // Onagre has the focus while running, don't offer to switch to it
+        Ok(windows
+            .into_iter()
+            .filter(|window| window.app_id.as_deref() != Some("onagre"))
+            .collect())
+    }
+
+    pub fn focus(&self, window: &Window) -> Result<()> {
+        match self {
+            WindowManager::Ipc(socket) => ipc::focus(socket, &window.id),
+            WindowManager::Ewmh => ewmh::focus(&window.id),
+        }
+    }
+
+    pub fn outputs(&self) -> Result<Vec<Output>> {
+        match self {
+            WindowManager::Ipc(socket) => ipc::outputs(socket),
+            WindowManager::Ewmh => ewmh::outputs(self.cursor()),
+        }
+    }
+
+    // The pointer position, sway and i3 don't expose it
+    pub fn cursor(&self) -> Option<(i32, i32)> {
+        match self {
+            WindowManager::Ipc(_) => None,
+            WindowManager::Ewmh => ewmh::cursor(),
+        }
+    }
+
+    // Float the onagre window above the others at the given position.
+    // X11 windows are placed on creation and kept on top by winit.
+    pub fn float(&self, title: &str, position: (i32, i32)) -> Result<()> {
+        match self {
+            WindowManager::Ipc(socket) => ipc::float(socket, title, position),
+            WindowManager::Ewmh => Ok(()),
+        }
+    }
+
+    pub fn set_opacity(&self, title: &str, opacity: f32) -> Result<()> {
+        let opacity = opacity.clamp(0.0, 1.0);
+        match self {
+            WindowManager::Ipc(socket) => ipc::set_opacity(socket, title, opacity),
+            WindowManager::Ewmh => ewmh::set_opacity(title, opacity),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::windows::WindowManager;
+    use speculoos::prelude::*;
+    use std::collections::HashMap;
+    use std::ffi::OsString;
+    use std::path::PathBuf;
+
+    fn detect_with(vars: &[(&str, &str)]) -> Option<WindowManager> {
+        let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+        WindowManager::detect_with(|key| vars.get(key).map(OsString::from))
+    }
+
+    #[test]
+    fn should_detect_window_manager_from_env() {
+        let sway = [
+            ("SWAYSOCK", "/run/user/1000/sway-ipc.sock"),
+            ("WAYLAND_DISPLAY", "wayland-1"),
+            ("DISPLAY", ":0"),
+        ];
+
+        assert_that!(detect_with(&sway)).is_equal_to(Some(WindowManager::Ipc(PathBuf::from(
+            "/run/user/1000/sway-ipc.sock",
+        ))));
+        assert_that!(detect_with(&[("DISPLAY", ":0")])).is_equal_to(Some(WindowManager::Ewmh));
+        assert_that!(detect_with(&[
+            ("WAYLAND_DISPLAY", "wayland-0"),
+            ("DISPLAY", ":0")
+        ]))
+        .is_none();
+        assert_that!(detect_with(&[])).is_none();
+    }
+}